@@ -0,0 +1,218 @@
+//! The command lines of the day binaries. Both parts of a day take the same
+//! arguments and only differ in their default strategy or metric.
+
+use crate::crossing::Crossings;
+use crate::fuel::{Breakdown, Strategy};
+use crate::grid::{format_vector_list, Point, VectorList};
+use crate::input::read_line;
+use crate::manifest::{self, Format, Policy};
+use crate::notation;
+use crate::render::Diagram;
+use crate::route;
+use crate::segment::{self, intersect_all, segments, Metric};
+use crate::viewport::Viewport;
+use crate::wire::Pallet;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::process;
+use std::thread;
+use std::time::Duration;
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+
+enum FuelReport {
+    Total,
+    Table,
+    Json,
+}
+
+/// Reads a module manifest from stdin and reports the fuel it needs.
+///
+/// Arguments: `--table`, `--json`, or a manifest format.
+pub fn day1(strategy: Strategy) {
+    let mut format = Format::Auto;
+    let mut report = FuelReport::Total;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--table" => report = FuelReport::Table,
+            "--json" => report = FuelReport::Json,
+            f => format = f.parse().expect("Invalid format"),
+        }
+    }
+    let stdin = io::stdin();
+    let manifest = manifest::read(stdin.lock(), format, Policy::Collect)
+        .unwrap_or_else(|e| fail(&format!("Manifest read failed: {}", e)));
+    for e in &manifest.errors {
+        eprintln!("Skipping {}", e);
+    }
+    let breakdown = Breakdown::new(strategy, manifest.modules).expect("Fuel overflow");
+    match report {
+        FuelReport::Total => println!("Fuel required: {}", breakdown.total),
+        FuelReport::Table => println!("{}", breakdown),
+        FuelReport::Json => println!("{}", breakdown.to_json()),
+    }
+}
+
+enum WireReport {
+    Best,
+    Json,
+    Csv,
+    Svg(String),
+    Png(String),
+    Gif(String),
+    Frames,
+    Rewire,
+    View,
+}
+
+/// Largest side of rendered pictures, in pixels
+const PICTURE_SIZE: u32 = 1000;
+/// Frames in animations, and hundredths of a second between them
+const GIF_FRAMES: u64 = 100;
+const GIF_DELAY: u16 = 5;
+/// Frames shown in the terminal
+const ANIMATION_FRAMES: u64 = 100;
+
+/// Reads two wires from stdin and reports on their crossings.
+///
+/// Arguments: a metric name, or one of `--json`, `--csv`, `--svg PATH`,
+/// `--png PATH`, `--gif PATH`, `--frames`, `--rewire` and `--view`.
+pub fn day3(default_metric: Metric<'static>) {
+    let mut metric = default_metric;
+    let mut report = WireReport::Best;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => report = WireReport::Json,
+            "--csv" => report = WireReport::Csv,
+            "--svg" => report = WireReport::Svg(args.next().expect("--svg needs a path")),
+            "--png" => report = WireReport::Png(args.next().expect("--png needs a path")),
+            "--gif" => report = WireReport::Gif(args.next().expect("--gif needs a path")),
+            "--frames" => report = WireReport::Frames,
+            "--rewire" => report = WireReport::Rewire,
+            "--view" => report = WireReport::View,
+            m => metric = m.parse().expect("Invalid metric"),
+        }
+    }
+    let wires = [read_wire(), read_wire()];
+    let found = segment::best(&wires[0], &wires[1], &metric);
+    let best = found.map(|(p, _)| p);
+    match report {
+        WireReport::Best => {
+            print_small(&wires, best);
+            println!("Closest crosspoint={:?}", found);
+        }
+        WireReport::Json => println!("{}", crossings(&wires).to_json()),
+        WireReport::Csv => print!("{}", crossings(&wires).to_csv()),
+        WireReport::Svg(path) => {
+            let svg = Diagram::new(&wires, best).to_svg(PICTURE_SIZE);
+            fs::write(path, svg).expect("Failed to write SVG");
+        }
+        WireReport::Png(path) => {
+            let png = Diagram::new(&wires, best).to_png(PICTURE_SIZE);
+            fs::write(path, png).expect("Failed to write PNG");
+        }
+        WireReport::Gif(path) => {
+            let gif = Diagram::new(&wires, best).to_gif(PICTURE_SIZE, GIF_FRAMES, GIF_DELAY);
+            fs::write(path, gif).expect("Failed to write GIF");
+        }
+        WireReport::Rewire => match route::rewire(&wires[0], &wires[1]) {
+            Some(route) => println!("{}", format_vector_list(&route)),
+            None => println!("No route"),
+        },
+        WireReport::Frames => {
+            let (cols, rows) = termion::terminal_size().unwrap_or((80, 24));
+            // Room for the border
+            let size = (cols.saturating_sub(2).into(), rows.saturating_sub(3).into());
+            Pallet::frames(&wires, best, ANIMATION_FRAMES, size, |frame| {
+                // Clear the terminal before every frame
+                print!("\x1b[2J\x1b[H{}", frame);
+                io::stdout().flush().expect("Failed to write frame");
+                thread::sleep(Duration::from_millis(50));
+            });
+        }
+        WireReport::View => {
+            let mut pallet = Pallet::new();
+            pallet.draw(&wires[0]);
+            pallet.draw(&wires[1]);
+            view(&pallet, best).expect("Terminal view failed");
+        }
+    }
+}
+
+/// Prints `message` and exits with a failure status
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+/// Reads a wire from a line of stdin, or exits showing what is wrong with it
+fn read_wire() -> VectorList {
+    let line = read_line();
+    let line = line.trim_end();
+    notation::parse_wire(line).unwrap_or_else(|e| fail(&e.render(line)))
+}
+
+/// Prints layouts that fit in a terminal, highlighting `best`. The size is
+/// checked on the segments, so large layouts are never drawn cell by cell.
+fn print_small(wires: &[VectorList], best: Option<Point>) {
+    let all: Vec<_> = wires.iter().flat_map(segments).collect();
+    let bounds = segment::extents(&all);
+    if bounds.width() > 40 || bounds.height() > 40 {
+        return;
+    }
+    let mut pallet = Pallet::new();
+    for w in wires {
+        pallet.draw(w);
+    }
+    pallet.print(best);
+}
+
+fn crossings(wires: &[VectorList; 2]) -> Crossings {
+    let found = intersect_all(&segments(&wires[0]), &segments(&wires[1]));
+    Crossings::from_intersections(&found)
+}
+
+/// Shows `pallet` in the terminal, panning with the arrow keys or hjkl and
+/// zooming with + and -, until q is pressed. Keys are read from the terminal
+/// itself, as stdin holds the wires.
+fn view(pallet: &Pallet, highlight: Option<Point>) -> io::Result<()> {
+    let tty = termion::get_tty()?;
+    let mut out = tty.try_clone()?.into_raw_mode()?;
+    let (cols, rows) = termion::terminal_size().unwrap_or((80, 24));
+    // Room for the border and a status line
+    let (width, height) = (cols.saturating_sub(2), rows.saturating_sub(3));
+    let mut viewport = Viewport::fit(pallet.extents(), width.into(), height.into());
+    let mut keys = tty.keys();
+    loop {
+        write!(
+            out,
+            "{}{}",
+            termion::clear::All,
+            termion::cursor::Goto(1, 1)
+        )?;
+        for line in viewport.render(pallet, highlight).lines() {
+            write!(out, "{}\r\n", line)?;
+        }
+        write!(
+            out,
+            "center ({}, {}), {} cells per character; arrows/hjkl pan, +/- zoom, q quits",
+            viewport.center.x, viewport.center.y, viewport.zoom
+        )?;
+        out.flush()?;
+        let (dx, dy) = (width as i64 / 4, height as i64 / 4);
+        match keys.next().transpose()? {
+            Some(Key::Left) | Some(Key::Char('h')) => viewport.pan(-dx, 0),
+            Some(Key::Right) | Some(Key::Char('l')) => viewport.pan(dx, 0),
+            Some(Key::Up) | Some(Key::Char('k')) => viewport.pan(0, -dy),
+            Some(Key::Down) | Some(Key::Char('j')) => viewport.pan(0, dy),
+            Some(Key::Char('+')) | Some(Key::Char('=')) => viewport.zoom_in(),
+            Some(Key::Char('-')) => viewport.zoom_out(),
+            Some(Key::Char('q')) | Some(Key::Esc) | Some(Key::Ctrl('c')) | None => break,
+            Some(_) => (),
+        }
+    }
+    write!(out, "{}\r\n", termion::clear::All)
+}
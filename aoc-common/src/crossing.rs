@@ -0,0 +1,175 @@
+//! Every crossing of a wire layout with where and how the wires got there,
+//! for reports and other tools.
+
+use crate::grid::Point;
+use crate::segment::Intersection;
+use crate::wire::Pallet;
+use serde::Serialize;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Crossing {
+    pub x: i64,
+    pub y: i64,
+    /// Manhattan distance to the origin
+    pub manhattan: u64,
+    /// The wires meeting here, in order
+    pub wires: Vec<usize>,
+    /// Steps each of those wires takes to first get here
+    pub steps: Vec<u64>,
+}
+
+impl Crossing {
+    pub fn point(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    pub fn total_steps(&self) -> u64 {
+        self.steps.iter().sum()
+    }
+}
+
+/// Crossings, sorted by coordinates unless sorted otherwise.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Crossings(pub Vec<Crossing>);
+
+impl Crossings {
+    pub fn from_pallet(pallet: &Pallet) -> Crossings {
+        Crossings(
+            pallet
+                .crossed_by(2)
+                .into_iter()
+                .map(|(p, wires)| Crossing {
+                    x: p.x,
+                    y: p.y,
+                    manhattan: p.manhattan(),
+                    steps: wires
+                        .iter()
+                        .map(|&w| pallet.steps(w, p).expect("Wire visits its crossing"))
+                        .collect(),
+                    wires,
+                })
+                .collect(),
+        )
+    }
+
+    /// From the crossings of two wires, numbered 0 and 1, as found by
+    /// `intersect_all`.
+    pub fn from_intersections(intersections: &[Intersection]) -> Crossings {
+        let mut crossings: Vec<Crossing> = Vec::new();
+        let mut sorted = intersections.to_vec();
+        sorted.sort_by_key(|i| i.point);
+        for i in sorted {
+            match crossings.last_mut() {
+                // A wire passing through twice counts from its first visit
+                Some(c) if c.point() == i.point => {
+                    c.steps[0] = c.steps[0].min(i.steps.0);
+                    c.steps[1] = c.steps[1].min(i.steps.1);
+                }
+                _ => crossings.push(Crossing {
+                    x: i.point.x,
+                    y: i.point.y,
+                    manhattan: i.point.manhattan(),
+                    wires: vec![0, 1],
+                    steps: vec![i.steps.0, i.steps.1],
+                }),
+            }
+        }
+        Crossings(crossings)
+    }
+
+    pub fn sort_by_manhattan(&mut self) {
+        self.0.sort_by_key(|c| (c.manhattan, c.x, c.y));
+    }
+
+    pub fn sort_by_total_steps(&mut self) {
+        self.0.sort_by_key(|c| (c.total_steps(), c.x, c.y));
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Crossing serialization failed")
+    }
+
+    /// One crossing per row, with the wires and their steps separated by `;`
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("x,y,manhattan,total_steps,wires,steps\n");
+        for c in &self.0 {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                c.x,
+                c.y,
+                c.manhattan,
+                c.total_steps(),
+                join(&c.wires),
+                join(&c.steps)
+            )
+            .expect("Writing to a String cannot fail");
+        }
+        csv
+    }
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+#[cfg(test)]
+use crate::testing::{pallet, wires, REFERENCE};
+
+#[cfg(test)]
+fn segment_crossings(a: &str, b: &str) -> Crossings {
+    use crate::segment::{intersect_all, segments};
+    let wires = wires(&[a, b]);
+    Crossings::from_intersections(&intersect_all(&segments(&wires[0]), &segments(&wires[1])))
+}
+
+#[test]
+fn crossing_report() {
+    let mut crossings = Crossings::from_pallet(&pallet(&REFERENCE));
+    assert_eq!(
+        crossings.0[0],
+        Crossing {
+            x: 3,
+            y: -3,
+            manhattan: 6,
+            wires: vec![0, 1],
+            steps: vec![20, 20]
+        }
+    );
+    crossings.sort_by_total_steps();
+    assert_eq!(crossings.0[0].point(), Point::new(6, -5));
+    assert_eq!(
+        crossings.to_csv(),
+        "x,y,manhattan,total_steps,wires,steps\n6,-5,11,30,0;1,15;15\n3,-3,6,40,0;1,20;20\n"
+    );
+    let json: serde_json::Value = serde_json::from_str(&crossings.to_json()).unwrap();
+    assert_eq!(json[1]["steps"][0], 20);
+    assert_eq!(json[1]["wires"][1], 1);
+}
+
+#[test]
+fn segment_crossings_match_pallet() {
+    let (a, b) = (
+        "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+        "U62,R66,U55,R34,D71,R55,D58,R83",
+    );
+    assert_eq!(
+        segment_crossings(a, b),
+        Crossings::from_pallet(&pallet(&[a, b]))
+    );
+}
+
+#[test]
+fn overlaps_list_every_cell() {
+    let (a, b) = ("R10", "U1,R2,D1,R8");
+    let crossings = segment_crossings(a, b);
+    let xs: Vec<i64> = crossings.0.iter().map(|c| c.x).collect();
+    assert_eq!(xs, [2, 3, 4, 5, 6, 7, 8, 9, 10]);
+    assert_eq!(crossings, Crossings::from_pallet(&pallet(&[a, b])));
+}
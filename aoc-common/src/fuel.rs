@@ -0,0 +1,396 @@
+use crate::fuel_table;
+use serde::Serialize;
+use std::fmt;
+use std::iter::Sum;
+use std::num::ParseIntError;
+use std::ops::Add;
+use std::str::FromStr;
+
+/// Mass of a module, or of the fuel needed to launch it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Mass(pub u64);
+
+/// A fuel total no longer fits in a `Mass`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Fuel total overflowed")
+    }
+}
+
+impl Mass {
+    /// Fuel needed for this mass alone, `mass / 3 - 2`. Masses too small to
+    /// need any fuel get zero instead of underflowing.
+    pub fn fuel(self) -> Mass {
+        Mass(fuel_table::fuel(self.0))
+    }
+
+    pub fn checked_add(self, other: Mass) -> Option<Mass> {
+        self.0.checked_add(other.0).map(Mass)
+    }
+
+    pub fn saturating_add(self, other: Mass) -> Mass {
+        Mass(self.0.saturating_add(other.0))
+    }
+}
+
+impl Add for Mass {
+    type Output = Mass;
+    fn add(self, other: Mass) -> Mass {
+        self.checked_add(other).expect("Mass overflow")
+    }
+}
+
+impl Sum for Mass {
+    fn sum<I: Iterator<Item = Mass>>(iter: I) -> Mass {
+        iter.fold(Mass(0), Add::add)
+    }
+}
+
+impl FromStr for Mass {
+    type Err = ParseIntError;
+    fn from_str(s: &str) -> Result<Mass, ParseIntError> {
+        s.parse().map(Mass)
+    }
+}
+
+impl fmt::Display for Mass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// How the fuel for a module is calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Strategy {
+    /// Fuel for the module mass only
+    Simple,
+    /// Fuel for the module mass, plus fuel for that fuel and so on
+    Recursive,
+}
+
+impl Strategy {
+    /// The fuel needed at each stage: first for the module itself, then
+    /// (for `Recursive`) for the fuel added by the previous stage.
+    pub fn stages(self, mass: Mass) -> Stages {
+        Stages {
+            fuel: mass.fuel(),
+            recursive: self == Strategy::Recursive,
+        }
+    }
+
+    pub fn fuel_required(self, mass: Mass) -> Mass {
+        // Every stage is at most a third of the previous one, so the total
+        // can never exceed the module mass and thus not overflow
+        match self {
+            Strategy::Simple => mass.fuel(),
+            Strategy::Recursive => Mass(fuel_table::shared().fuel_required(mass.0)),
+        }
+    }
+
+    /// The largest single module mass whose fuel fits within `budget`.
+    pub fn max_mass(self, budget: Mass) -> Mass {
+        // The fuel for a module is never less than `mass / 3 - 2`, which
+        // bounds the search from above
+        let mut low = 0;
+        let mut high = budget.0.saturating_mul(3).saturating_add(8);
+        if self.fuel_required(Mass(high)) <= budget {
+            return Mass(high);
+        }
+        // Fuel never decreases with mass; invariant: low fits, high does not
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if self.fuel_required(Mass(mid)) <= budget {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Mass(low)
+    }
+
+    /// Splits `budget` as evenly as possible over `count` modules and
+    /// returns the largest mass each of them can have.
+    pub fn max_masses(self, budget: Mass, count: u64) -> Vec<Mass> {
+        if count == 0 {
+            return Vec::new();
+        }
+        let share = budget.0 / count;
+        let extra = budget.0 % count;
+        (0..count)
+            .map(|i| {
+                let share = if i < extra { share + 1 } else { share };
+                self.max_mass(Mass(share))
+            })
+            .collect()
+    }
+}
+
+pub struct Stages {
+    fuel: Mass,
+    recursive: bool,
+}
+
+impl Iterator for Stages {
+    type Item = Mass;
+    fn next(&mut self) -> Option<Mass> {
+        if self.fuel == Mass(0) {
+            return None;
+        }
+        let fuel = self.fuel;
+        self.fuel = if self.recursive { fuel.fuel() } else { Mass(0) };
+        Some(fuel)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Module {
+    pub name: Option<String>,
+    pub mass: Mass,
+}
+
+impl Module {
+    pub fn new(mass: Mass) -> Module {
+        Module { name: None, mass }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PlanEntry {
+    pub module: Module,
+    pub fuel: Mass,
+}
+
+/// Fuel needed per module, and in total, for a manifest of modules.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Plan {
+    pub strategy: Strategy,
+    pub entries: Vec<PlanEntry>,
+    pub total: Mass,
+}
+
+impl Plan {
+    pub fn new<I: IntoIterator<Item = Module>>(
+        strategy: Strategy,
+        manifest: I,
+    ) -> Result<Plan, Overflow> {
+        let mut entries = Vec::new();
+        let mut total = Mass(0);
+        for module in manifest {
+            let fuel = strategy.fuel_required(module.mass);
+            total = total.checked_add(fuel).ok_or(Overflow)?;
+            entries.push(PlanEntry { module, fuel });
+        }
+        Ok(Plan {
+            strategy,
+            entries,
+            total,
+        })
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<20} {:>20} {:>20}", "Module", "Mass", "Fuel")?;
+        for (i, e) in self.entries.iter().enumerate() {
+            let name = match &e.module.name {
+                Some(name) => name.clone(),
+                None => format!("#{}", i + 1),
+            };
+            writeln!(f, "{:<20} {:>20} {:>20}", name, e.module.mass, e.fuel)?;
+        }
+        write!(f, "{:<20} {:>20} {:>20}", "Total", "", self.total)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct ModuleBreakdown {
+    #[serde(flatten)]
+    pub module: Module,
+    pub stages: Vec<Mass>,
+    pub fuel: Mass,
+}
+
+/// The fuel cascade of every module, with the sum of each stage across all
+/// modules.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Breakdown {
+    pub strategy: Strategy,
+    pub modules: Vec<ModuleBreakdown>,
+    pub stage_totals: Vec<Mass>,
+    pub total: Mass,
+}
+
+impl Breakdown {
+    pub fn new<I: IntoIterator<Item = Module>>(
+        strategy: Strategy,
+        manifest: I,
+    ) -> Result<Breakdown, Overflow> {
+        let mut modules = Vec::new();
+        let mut stage_totals: Vec<Mass> = Vec::new();
+        let mut total = Mass(0);
+        for module in manifest {
+            let stages: Vec<Mass> = strategy.stages(module.mass).collect();
+            for (i, &fuel) in stages.iter().enumerate() {
+                match stage_totals.get_mut(i) {
+                    Some(t) => *t = t.checked_add(fuel).ok_or(Overflow)?,
+                    None => stage_totals.push(fuel),
+                }
+            }
+            let fuel = stages.iter().cloned().sum();
+            total = total.checked_add(fuel).ok_or(Overflow)?;
+            modules.push(ModuleBreakdown {
+                module,
+                stages,
+                fuel,
+            });
+        }
+        Ok(Breakdown {
+            strategy,
+            modules,
+            stage_totals,
+            total,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Breakdown serialization failed")
+    }
+}
+
+impl fmt::Display for Breakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:<20} {:>12}", "Module", "Mass")?;
+        for i in 0..self.stage_totals.len() {
+            write!(f, " {:>12}", format!("Stage {}", i + 1))?;
+        }
+        writeln!(f, " {:>12}", "Fuel")?;
+        for (i, m) in self.modules.iter().enumerate() {
+            let name = match &m.module.name {
+                Some(name) => name.clone(),
+                None => format!("#{}", i + 1),
+            };
+            write!(f, "{:<20} {:>12}", name, m.module.mass)?;
+            for i in 0..self.stage_totals.len() {
+                match m.stages.get(i) {
+                    Some(fuel) => write!(f, " {:>12}", fuel)?,
+                    None => write!(f, " {:>12}", "")?,
+                }
+            }
+            writeln!(f, " {:>12}", m.fuel)?;
+        }
+        write!(f, "{:<20} {:>12}", "Total", "")?;
+        for fuel in &self.stage_totals {
+            write!(f, " {:>12}", fuel)?;
+        }
+        write!(f, " {:>12}", self.total)
+    }
+}
+
+#[test]
+fn simple_works() {
+    let fuel = |m| Strategy::Simple.fuel_required(Mass(m));
+    assert_eq!(fuel(12), Mass(2));
+    assert_eq!(fuel(14), Mass(2));
+    assert_eq!(fuel(1969), Mass(654));
+    assert_eq!(fuel(100756), Mass(33583));
+}
+
+#[test]
+fn recursive_works() {
+    let fuel = |m| Strategy::Recursive.fuel_required(Mass(m));
+    assert_eq!(fuel(12), Mass(2));
+    assert_eq!(fuel(14), Mass(2));
+    assert_eq!(fuel(1969), Mass(966));
+    assert_eq!(fuel(100756), Mass(50346));
+}
+
+#[test]
+fn closed_form_matches_stages() {
+    for m in (0..100_000).chain(u64::MAX - 1000..=u64::MAX) {
+        let stages: Mass = Strategy::Recursive.stages(Mass(m)).sum();
+        assert_eq!(Strategy::Recursive.fuel_required(Mass(m)), stages);
+    }
+}
+
+#[test]
+fn small_masses_need_no_fuel() {
+    for m in 0..9 {
+        assert_eq!(Strategy::Simple.fuel_required(Mass(m)), Mass(0));
+        assert_eq!(Strategy::Recursive.fuel_required(Mass(m)), Mass(0));
+    }
+    assert!(Strategy::Recursive.fuel_required(Mass(u64::MAX)) < Mass(u64::MAX / 2));
+}
+
+#[test]
+fn plan_works() {
+    let manifest = vec![Module::new(Mass(12)), Module::new(Mass(1969))];
+    let plan = Plan::new(Strategy::Recursive, manifest).unwrap();
+    assert_eq!(plan.total, Mass(968));
+    assert_eq!(plan.entries[1].fuel, Mass(966));
+}
+
+#[test]
+fn plan_overflow() {
+    let manifest = vec![Module::new(Mass(u64::MAX)); 4];
+    assert_eq!(Plan::new(Strategy::Simple, manifest), Err(Overflow));
+}
+
+#[test]
+fn stages_works() {
+    let stages: Vec<Mass> = Strategy::Recursive.stages(Mass(1969)).collect();
+    assert_eq!(
+        stages,
+        [654, 216, 70, 21, 5]
+            .iter()
+            .map(|&m| Mass(m))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(Strategy::Simple.stages(Mass(1969)).count(), 1);
+    assert_eq!(Strategy::Simple.stages(Mass(3)).count(), 0);
+}
+
+#[test]
+fn breakdown_works() {
+    let manifest = vec![Module::new(Mass(14)), Module::new(Mass(1969))];
+    let b = Breakdown::new(Strategy::Recursive, manifest).unwrap();
+    assert_eq!(b.modules[0].stages, [Mass(2)]);
+    assert_eq!(b.stage_totals[0], Mass(656));
+    assert_eq!(b.stage_totals[1], Mass(216));
+    assert_eq!(b.total, Mass(968));
+    let json: serde_json::Value = serde_json::from_str(&b.to_json()).unwrap();
+    assert_eq!(json["modules"][1]["mass"], 1969);
+    assert_eq!(json["modules"][1]["stages"][4], 5);
+    assert_eq!(json["total"], 968);
+}
+
+#[test]
+fn max_mass_works() {
+    assert_eq!(Strategy::Simple.max_mass(Mass(0)), Mass(8));
+    assert_eq!(Strategy::Simple.max_mass(Mass(2)), Mass(14));
+    assert_eq!(Strategy::Simple.max_mass(Mass(654)), Mass(1970));
+    assert_eq!(Strategy::Recursive.max_mass(Mass(0)), Mass(8));
+    assert_eq!(Strategy::Recursive.max_mass(Mass(966)), Mass(1970));
+    assert_eq!(Strategy::Recursive.max_mass(Mass(u64::MAX)), Mass(u64::MAX));
+    for strategy in &[Strategy::Simple, Strategy::Recursive] {
+        for budget in 0..1000 {
+            let m = strategy.max_mass(Mass(budget));
+            assert!(strategy.fuel_required(m) <= Mass(budget));
+            assert!(strategy.fuel_required(Mass(m.0 + 1)) > Mass(budget));
+        }
+    }
+}
+
+#[test]
+fn max_masses_works() {
+    let masses = Strategy::Simple.max_masses(Mass(5), 2);
+    assert_eq!(masses, [Mass(17), Mass(14)]);
+    let fuel: Mass = masses
+        .iter()
+        .map(|&m| Strategy::Simple.fuel_required(m))
+        .sum();
+    assert_eq!(fuel, Mass(5));
+    assert!(Strategy::Recursive.max_masses(Mass(5), 0).is_empty());
+}
@@ -0,0 +1,166 @@
+//! Recursive fuel calculation for huge manifests, without walking the stages.
+//!
+//! With `n = mass + 3`, stage `k` of the cascade needs `n / 3^k - 3` fuel, so
+//! the total is the sum of those terms while they stay positive. Summing
+//! `n / 3^k` over all `k` gives `(n - s)/2`, where `s` is the base 3 digit sum
+//! of `n`, and the terms left out are exactly `3` for every stage but the
+//! last plus the leading digit. For `n` with `j + 1` base 3 digits, the
+//! leading one being `d`:
+//!
+//! ```text
+//! total = (n - s)/2 - 3(j - 1) - d
+//! ```
+//!
+//! The digit sum, digit count and leading digit of every 8 digit chunk are
+//! memoized in a table, so a 64-bit mass takes at most 6 lookups instead of
+//! up to 40 stages. Works for any unsigned integer width.
+
+use std::convert::TryFrom;
+use std::ops::{Add, Div, Mul, Sub};
+use std::sync::OnceLock;
+
+pub trait Unsigned:
+    Copy
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + From<u32>
+{
+    fn saturating_sub(self, other: Self) -> Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn to_usize(self) -> Option<usize>;
+}
+
+macro_rules! unsigned {
+    ($($t:ty),*) => {
+        $(impl Unsigned for $t {
+            fn saturating_sub(self, other: Self) -> Self {
+                <$t>::saturating_sub(self, other)
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn to_usize(self) -> Option<usize> {
+                usize::try_from(self).ok()
+            }
+        })*
+    };
+}
+
+unsigned!(u32, u64, u128);
+
+/// Fuel needed for `mass` alone, saturating at zero.
+pub fn fuel<T: Unsigned>(mass: T) -> T {
+    (mass / T::from(3)).saturating_sub(T::from(2))
+}
+
+/// Fuel needed for `mass` and all the fuel added for it, one stage at a time.
+pub fn recursive_fuel<T: Unsigned>(mass: T) -> T {
+    let zero = T::from(0);
+    let mut total = zero;
+    let mut f = fuel(mass);
+    while f > zero {
+        total = total + f;
+        f = fuel(f);
+    }
+    total
+}
+
+const CHUNK_DIGITS: u32 = 8;
+const CHUNK: u32 = 6561; // 3^CHUNK_DIGITS
+
+#[derive(Clone, Copy, Default)]
+struct Digits {
+    sum: u8,
+    len: u8,
+    lead: u8,
+}
+
+pub struct FuelTable {
+    chunks: Vec<Digits>,
+}
+
+impl FuelTable {
+    pub fn new() -> FuelTable {
+        let mut chunks = vec![Digits::default(); CHUNK as usize];
+        for i in 1..CHUNK as usize {
+            // Strip the last digit, everything else is already known
+            let rest = chunks[i / 3];
+            let digit = (i % 3) as u8;
+            chunks[i] = Digits {
+                sum: rest.sum + digit,
+                len: rest.len + 1,
+                lead: if rest.len == 0 { digit } else { rest.lead },
+            };
+        }
+        FuelTable { chunks }
+    }
+
+    pub fn fuel_required<T: Unsigned>(&self, mass: T) -> T {
+        let n = match mass.checked_add(T::from(3)) {
+            Some(n) => n,
+            None => return recursive_fuel(mass),
+        };
+        let chunk = T::from(CHUNK);
+        let mut rest = n;
+        let mut sum = 0;
+        let mut digits = 0;
+        loop {
+            let q = rest / chunk;
+            let r = (rest - q * chunk).to_usize().expect("Chunk fits usize");
+            let d = self.chunks[r];
+            sum += u32::from(d.sum);
+            if q == T::from(0) {
+                // n >= 3, so there are always at least two digits
+                let j = digits + u32::from(d.len) - 1;
+                let half = (n - T::from(sum)) / T::from(2);
+                return half - T::from(3 * (j - 1) + u32::from(d.lead));
+            }
+            digits += CHUNK_DIGITS;
+            rest = q;
+        }
+    }
+}
+
+/// A table built on first use and kept for the rest of the run
+pub fn shared() -> &'static FuelTable {
+    static TABLE: OnceLock<FuelTable> = OnceLock::new();
+    TABLE.get_or_init(FuelTable::new)
+}
+
+impl Default for FuelTable {
+    fn default() -> FuelTable {
+        FuelTable::new()
+    }
+}
+
+#[test]
+fn table_matches_recursion() {
+    let table = FuelTable::new();
+    for m in 0..100_000u64 {
+        assert_eq!(table.fuel_required(m), recursive_fuel(m));
+    }
+    assert_eq!(table.fuel_required(1969u32), 966);
+    assert_eq!(table.fuel_required(100756u32), 50346);
+    assert_eq!(table.fuel_required(u64::MAX), recursive_fuel(u64::MAX));
+}
+
+#[test]
+fn wide_integers() {
+    let table = FuelTable::new();
+    let mut m: u128 = 1;
+    for _ in 0..1000 {
+        m = m
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        assert_eq!(table.fuel_required(m), recursive_fuel(m));
+    }
+    assert_eq!(
+        recursive_fuel(u32::MAX),
+        recursive_fuel(u32::MAX as u64) as u32
+    );
+}
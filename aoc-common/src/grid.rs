@@ -0,0 +1,522 @@
+use crate::notation;
+use std::fmt;
+use std::hash::Hash;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Vector {
+    Up(usize),
+    Down(usize),
+    Left(usize),
+    Right(usize),
+}
+
+pub type VectorList = Vec<Vector>;
+
+/// Coordinate axis, for mirroring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+pub trait VectorOps {
+    /// The smallest box holding the whole path, starting from the origin
+    fn bounding_box(&self) -> BoundingBox;
+    /// Where the wire ends, starting from the origin
+    fn end(&self) -> Point;
+    /// The same path walked backwards, from its end to its start
+    fn reversed(&self) -> VectorList;
+    /// The path turned clockwise by `quarter_turns` quarter turns, negative
+    /// ones turning counterclockwise
+    fn rotated(&self, quarter_turns: i32) -> VectorList;
+    /// The path with its coordinates along `axis` negated
+    fn mirrored(&self, axis: Axis) -> VectorList;
+    /// The path with zero length moves dropped, consecutive moves in the
+    /// same direction merged and moves doubling back cancelled out. The end
+    /// stays the same, but retraced stretches are no longer visited.
+    fn simplified(&self) -> VectorList;
+    /// This path followed by `other`
+    fn concat(&self, other: &[Vector]) -> VectorList;
+    /// The corners of the path starting at `start`, both ends included
+    fn to_polyline(&self, start: Point) -> Vec<Point>;
+}
+
+impl VectorOps for VectorList {
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::of(self.to_polyline(Point::origin()))
+            .expect("A polyline has at least its start")
+    }
+
+    fn end(&self) -> Point {
+        *self
+            .to_polyline(Point::origin())
+            .last()
+            .expect("A polyline has at least its start")
+    }
+
+    fn reversed(&self) -> VectorList {
+        self.iter().rev().map(Vector::opposite).collect()
+    }
+
+    fn rotated(&self, quarter_turns: i32) -> VectorList {
+        let turns = quarter_turns.rem_euclid(4);
+        self.iter()
+            .map(|v| (0..turns).fold(v.clone(), |v, _| v.turned_clockwise()))
+            .collect()
+    }
+
+    fn mirrored(&self, axis: Axis) -> VectorList {
+        self.iter()
+            .map(|v| match (axis, v) {
+                (Axis::X, Vector::Left(_)) | (Axis::X, Vector::Right(_)) => v.opposite(),
+                (Axis::Y, Vector::Up(_)) | (Axis::Y, Vector::Down(_)) => v.opposite(),
+                _ => v.clone(),
+            })
+            .collect()
+    }
+
+    fn simplified(&self) -> VectorList {
+        let mut simple: VectorList = Vec::new();
+        for v in self.iter().filter(|v| !v.is_empty()) {
+            match simple.last() {
+                Some(last) if last.delta() == v.delta() => {
+                    let merged = last.with_len(last.len() + v.len());
+                    *simple.last_mut().unwrap() = merged;
+                }
+                Some(last) if last.delta() == v.opposite().delta() => {
+                    let last = simple.pop().unwrap();
+                    if last.len() > v.len() {
+                        simple.push(last.with_len(last.len() - v.len()));
+                    } else if v.len() > last.len() {
+                        // Whatever came before can neither merge with nor
+                        // cancel this, as it was not in line with `last`
+                        simple.push(v.with_len(v.len() - last.len()));
+                    }
+                }
+                _ => simple.push(v.clone()),
+            }
+        }
+        simple
+    }
+
+    fn concat(&self, other: &[Vector]) -> VectorList {
+        self.iter().chain(other).cloned().collect()
+    }
+
+    fn to_polyline(&self, start: Point) -> Vec<Point> {
+        let mut points = vec![start];
+        let mut p = start;
+        for v in self {
+            let (dx, dy) = v.delta();
+            let len = v.len() as i64;
+            p = Point::new(p.x + dx * len, p.y + dy * len);
+            points.push(p);
+        }
+        points
+    }
+}
+
+/// Two consecutive polyline points not on a horizontal or vertical line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotOrthogonal {
+    pub from: Point,
+    pub to: Point,
+}
+
+impl fmt::Display for NotOrthogonal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "({}, {}) to ({}, {}) is not a horizontal or vertical move",
+            self.from.x, self.from.y, self.to.x, self.to.y
+        )
+    }
+}
+
+/// The moves following the corners of `points`, skipping repeated points.
+pub fn from_polyline(points: &[Point]) -> Result<VectorList, NotOrthogonal> {
+    let mut vl = VectorList::new();
+    for pair in points.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let (dx, dy) = (to.x - from.x, to.y - from.y);
+        let len = (dx.unsigned_abs() + dy.unsigned_abs()) as usize;
+        vl.push(match (dx.signum(), dy.signum()) {
+            (0, 0) => continue,
+            (0, -1) => Vector::Up(len),
+            (0, 1) => Vector::Down(len),
+            (-1, 0) => Vector::Left(len),
+            (1, 0) => Vector::Right(len),
+            _ => return Err(NotOrthogonal { from, to }),
+        });
+    }
+    Ok(vl)
+}
+
+/// Parses a wire, panicking with a pointer to the problem if it is
+/// malformed. See `notation::parse_wire` for a version returning errors.
+pub fn parse_vector_list(text: &str) -> VectorList {
+    let text = text.trim_end();
+    notation::parse_wire(text).unwrap_or_else(|e| panic!("Invalid wire:\n{}", e.render(text)))
+}
+
+/// Writes a wire back in the notation `parse_vector_list` reads.
+pub fn format_vector_list(vl: &[Vector]) -> String {
+    vl.iter()
+        .map(Vector::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl fmt::Display for Vector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let dir = match self {
+            Vector::Up(_) => 'U',
+            Vector::Down(_) => 'D',
+            Vector::Left(_) => 'L',
+            Vector::Right(_) => 'R',
+        };
+        write!(f, "{}{}", dir, self.len())
+    }
+}
+
+impl Vector {
+    pub fn parse(text: &str) -> Vector {
+        match notation::parse_wire(text) {
+            Ok(vl) if vl.len() == 1 => vl[0].clone(),
+            Ok(_) => panic!("Expected a single move, got '{}'", text),
+            Err(e) => panic!("Invalid move:\n{}", e.render(text)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Vector::Up(l) => *l,
+            Vector::Down(l) => *l,
+            Vector::Right(l) => *l,
+            Vector::Left(l) => *l,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The same move in the opposite direction
+    pub fn opposite(&self) -> Vector {
+        match *self {
+            Vector::Up(l) => Vector::Down(l),
+            Vector::Down(l) => Vector::Up(l),
+            Vector::Right(l) => Vector::Left(l),
+            Vector::Left(l) => Vector::Right(l),
+        }
+    }
+
+    /// The same move turned a quarter clockwise, `Up` turning `Right`
+    pub fn turned_clockwise(&self) -> Vector {
+        match *self {
+            Vector::Up(l) => Vector::Right(l),
+            Vector::Right(l) => Vector::Down(l),
+            Vector::Down(l) => Vector::Left(l),
+            Vector::Left(l) => Vector::Up(l),
+        }
+    }
+
+    /// A move in the same direction, `len` long
+    pub fn with_len(&self, len: usize) -> Vector {
+        match self {
+            Vector::Up(_) => Vector::Up(len),
+            Vector::Down(_) => Vector::Down(len),
+            Vector::Right(_) => Vector::Right(len),
+            Vector::Left(_) => Vector::Left(len),
+        }
+    }
+
+    /// The single step this vector moves in, `Up` being negative y
+    pub fn delta(&self) -> (i64, i64) {
+        match self {
+            Vector::Up(_) => (0, -1),
+            Vector::Down(_) => (0, 1),
+            Vector::Right(_) => (1, 0),
+            Vector::Left(_) => (-1, 0),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    pub fn origin() -> Point {
+        Point::default()
+    }
+
+    /// Manhattan distance to the origin
+    pub fn manhattan(&self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs()
+    }
+
+    /// Manhattan distance to `other`
+    pub fn manhattan_to(&self, other: Point) -> u64 {
+        (self.x - other.x).unsigned_abs() + (self.y - other.y).unsigned_abs()
+    }
+
+    /// Chebyshev distance to the origin, diagonal steps counting as one
+    pub fn chebyshev(&self) -> u64 {
+        self.x.unsigned_abs().max(self.y.unsigned_abs())
+    }
+
+    pub fn step(&self, (dx, dy): (i64, i64)) -> Point {
+        Point::new(self.x + dx, self.y + dy)
+    }
+}
+
+/// A cell of a wire layout, flat or in space, the origin being the default.
+pub trait Coord: Copy + Eq + Ord + Hash + Default + fmt::Debug {
+    /// A single step, in any direction the wires may take
+    type Delta: Copy;
+
+    fn step(&self, delta: Self::Delta) -> Self;
+    /// Manhattan distance to the origin
+    fn manhattan(&self) -> u64;
+    /// Chebyshev distance to the origin
+    fn chebyshev(&self) -> u64;
+}
+
+impl Coord for Point {
+    type Delta = (i64, i64);
+
+    fn step(&self, delta: (i64, i64)) -> Point {
+        Point::step(self, delta)
+    }
+
+    fn manhattan(&self) -> u64 {
+        Point::manhattan(self)
+    }
+
+    fn chebyshev(&self) -> u64 {
+        Point::chebyshev(self)
+    }
+}
+
+/// The cells from `min` to `max`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundingBox {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl BoundingBox {
+    /// The box with corners `a` and `b`, in any order
+    pub fn new(a: Point, b: Point) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    /// The smallest box holding all of `points`, if there are any
+    pub fn of<I: IntoIterator<Item = Point>>(points: I) -> Option<BoundingBox> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(BoundingBox::new(first, first), |b, p| b.including(p)))
+    }
+
+    /// This box grown to hold `p`
+    pub fn including(&self, p: Point) -> BoundingBox {
+        self.union(&BoundingBox::new(p, p))
+    }
+
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox::new(
+            Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        )
+    }
+
+    /// The cells in both boxes, if they overlap at all
+    pub fn intersection(&self, other: &BoundingBox) -> Option<BoundingBox> {
+        let min = Point::new(self.min.x.max(other.min.x), self.min.y.max(other.min.y));
+        let max = Point::new(self.max.x.min(other.max.x), self.max.y.min(other.max.y));
+        if min.x <= max.x && min.y <= max.y {
+            Some(BoundingBox { min, max })
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.min.x <= p.x && p.x <= self.max.x && self.min.y <= p.y && p.y <= self.max.y
+    }
+
+    /// Number of columns of cells
+    pub fn width(&self) -> u64 {
+        (self.max.x - self.min.x) as u64 + 1
+    }
+
+    /// Number of rows of cells
+    pub fn height(&self) -> u64 {
+        (self.max.y - self.min.y) as u64 + 1
+    }
+}
+
+#[test]
+fn parse_vector() {
+    assert_eq!(Vector::parse("R100"), Vector::Right(100))
+}
+
+#[test]
+fn format_vectors() {
+    let text = "R8,U5,L5,D3";
+    assert_eq!(format_vector_list(&parse_vector_list(text)), text);
+}
+
+#[test]
+fn bounding_box() {
+    let v: Vec<Vector> = [Vector::parse("R100"), Vector::parse("U50")].to_vec();
+    let b = v.bounding_box();
+    assert_eq!(b, BoundingBox::new(Point::new(0, -50), Point::new(100, 0)));
+    assert_eq!((b.width(), b.height()), (101, 51));
+    // Nothing is doubled around the origin
+    let b = parse_vector_list("L3,D2,R10").bounding_box();
+    assert_eq!(b, BoundingBox::new(Point::new(-3, 0), Point::new(7, 2)));
+    assert_eq!((b.width(), b.height()), (11, 3));
+}
+
+#[test]
+fn box_operations() {
+    let a = BoundingBox::new(Point::new(2, 2), Point::new(-1, 0));
+    assert_eq!(a.min, Point::new(-1, 0));
+    let b = BoundingBox::new(Point::new(1, 1), Point::new(5, 5));
+    assert_eq!(
+        a.union(&b),
+        BoundingBox::new(Point::new(-1, 0), Point::new(5, 5))
+    );
+    assert_eq!(
+        a.intersection(&b),
+        Some(BoundingBox::new(Point::new(1, 1), Point::new(2, 2)))
+    );
+    let far = BoundingBox::new(Point::new(3, 3), Point::new(3, 3));
+    assert_eq!(a.intersection(&far), None);
+    assert!(b.contains(Point::new(3, 3)) && !a.contains(Point::new(3, 3)));
+    assert_eq!(BoundingBox::of(Vec::new()), None);
+    assert_eq!(a.including(Point::new(9, -9)).width(), 11);
+}
+
+#[test]
+fn transforms() {
+    let v = parse_vector_list("R8,U5,L5,D3");
+    assert_eq!(v.end(), Point::new(3, -2));
+    assert_eq!(v.reversed(), parse_vector_list("U3,R5,D5,L8"));
+    assert_eq!(v.rotated(1), parse_vector_list("D8,R5,U5,L3"));
+    assert_eq!(v.rotated(-1), parse_vector_list("U8,L5,D5,R3"));
+    assert_eq!(v.mirrored(Axis::X), parse_vector_list("L8,U5,R5,D3"));
+    assert_eq!(
+        parse_vector_list("R3,R2,L0,U4,D6,U1,L5,R2").simplified(),
+        parse_vector_list("R5,D1,L3")
+    );
+    assert_eq!(
+        v.to_polyline(Point::new(1, 1)),
+        [
+            Point::new(1, 1),
+            Point::new(9, 1),
+            Point::new(9, -4),
+            Point::new(4, -4),
+            Point::new(4, -1)
+        ]
+    );
+    assert_eq!(
+        from_polyline(&[Point::origin(), Point::new(1, 1)]),
+        Err(NotOrthogonal {
+            from: Point::origin(),
+            to: Point::new(1, 1)
+        })
+    );
+}
+
+#[cfg(test)]
+mod strategies {
+    use super::*;
+    use proptest::prelude::*;
+
+    pub fn vector() -> impl Strategy<Value = Vector> {
+        (0..4u8, 0..20usize).prop_map(|(d, l)| match d {
+            0 => Vector::Up(l),
+            1 => Vector::Down(l),
+            2 => Vector::Left(l),
+            _ => Vector::Right(l),
+        })
+    }
+
+    pub fn wire() -> impl Strategy<Value = VectorList> {
+        prop::collection::vec(vector(), 0..30)
+    }
+}
+
+#[cfg(test)]
+use proptest::prelude::*;
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn reversing_twice_is_identity(v in strategies::wire()) {
+        prop_assert_eq!(v.reversed().reversed(), v.clone());
+        let back = v.reversed().to_polyline(v.end());
+        let mut forth = v.to_polyline(Point::origin());
+        forth.reverse();
+        prop_assert_eq!(back, forth);
+    }
+
+    #[test]
+    fn rotation_turns_the_end(v in strategies::wire(), turns in -8..8i32) {
+        prop_assert_eq!(v.rotated(4), v.clone());
+        prop_assert_eq!(v.rotated(turns).rotated(-turns), v.clone());
+        let end = v.end();
+        prop_assert_eq!(v.rotated(1).end(), Point::new(-end.y, end.x));
+        prop_assert_eq!(v.rotated(2).end(), Point::new(-end.x, -end.y));
+    }
+
+    #[test]
+    fn mirroring_negates(v in strategies::wire()) {
+        let end = v.end();
+        prop_assert_eq!(v.mirrored(Axis::X).end(), Point::new(-end.x, end.y));
+        prop_assert_eq!(v.mirrored(Axis::Y).end(), Point::new(end.x, -end.y));
+        prop_assert_eq!(v.mirrored(Axis::Y).mirrored(Axis::Y), v.clone());
+    }
+
+    #[test]
+    fn simplifying_keeps_the_end(v in strategies::wire()) {
+        let simple = v.simplified();
+        prop_assert_eq!(simple.end(), v.end());
+        prop_assert_eq!(simple.simplified(), simple.clone());
+        prop_assert!(simple.iter().all(|m| !m.is_empty()));
+        for pair in simple.windows(2) {
+            let (a, b) = (pair[0].delta(), pair[1].delta());
+            prop_assert!(a != b && a != (-b.0, -b.1));
+        }
+        let len = |v: &VectorList| v.iter().map(Vector::len).sum::<usize>();
+        prop_assert!(len(&simple) <= len(&v));
+    }
+
+    #[test]
+    fn concatenation_adds_ends(a in strategies::wire(), b in strategies::wire()) {
+        let (ea, eb) = (a.end(), b.end());
+        prop_assert_eq!(a.concat(&b).end(), Point::new(ea.x + eb.x, ea.y + eb.y));
+        let mut joined = a.to_polyline(Point::origin());
+        joined.extend(b.to_polyline(ea).into_iter().skip(1));
+        prop_assert_eq!(a.concat(&b).to_polyline(Point::origin()), joined);
+    }
+
+    #[test]
+    fn polylines_round_trip(v in strategies::wire(), x in -50..50i64, y in -50..50i64) {
+        let points = v.to_polyline(Point::new(x, y));
+        let moves: VectorList = v.iter().filter(|m| !m.is_empty()).cloned().collect();
+        prop_assert_eq!(from_polyline(&points), Ok(moves));
+    }
+}
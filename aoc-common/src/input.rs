@@ -0,0 +1,10 @@
+use std::io;
+
+/// Reads a single line from stdin, including the trailing newline.
+pub fn read_line() -> String {
+    let mut text = String::new();
+    io::stdin()
+        .read_line(&mut text)
+        .expect("Failed to read stdin");
+    text
+}
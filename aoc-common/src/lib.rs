@@ -0,0 +1,24 @@
+//! Code shared between the days: their command lines, input and manifest
+//! reading, the rocket equation, the intcode machine, wire grid types,
+//! layouts, crossing reports, pictures and terminal views, loop analysis,
+//! rewiring, wires in 3D and with diagonals, and password rules and counting.
+
+pub mod cli;
+pub mod crossing;
+pub mod fuel;
+pub mod fuel_table;
+pub mod grid;
+pub mod input;
+pub mod intcode;
+pub mod loops;
+pub mod manifest;
+pub mod notation;
+pub mod password;
+pub mod render;
+pub mod route;
+pub mod segment;
+pub mod space;
+#[cfg(test)]
+mod testing;
+pub mod viewport;
+pub mod wire;
@@ -0,0 +1,142 @@
+//! Where a single wire crosses itself, the loops that makes, and the path
+//! it would take with those loops cut out.
+//!
+//! Crossings between wires count the steps to the first visit of a cell, so
+//! a wire looping back on itself is never noticed there, but the steps spent
+//! in its loops still count towards every crossing after them.
+
+use crate::grid::{from_polyline, Point, VectorList, VectorOps};
+use std::collections::HashMap;
+
+/// A cell the wire visits more than once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfCrossing {
+    pub point: Point,
+    /// Steps taken at every visit, in order
+    pub steps: Vec<u64>,
+}
+
+/// The wire leaving a cell and coming back to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Loop {
+    pub point: Point,
+    /// Steps taken when leaving and when coming back
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Loop {
+    pub fn len(&self) -> u64 {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Sorted by cell
+    pub self_crossings: Vec<SelfCrossing>,
+    /// Between every two consecutive visits to a cell, sorted by start
+    pub loops: Vec<Loop>,
+    /// The wire with every loop cut out, as soon as it closes
+    pub shortcut: VectorList,
+}
+
+impl Analysis {
+    /// Steps saved by taking the shortcut
+    pub fn saved(&self, wire: &VectorList) -> u64 {
+        let len = |vl: &VectorList| vl.iter().map(|v| v.len() as u64).sum::<u64>();
+        len(wire) - len(&self.shortcut)
+    }
+}
+
+pub fn analyze(wire: &VectorList) -> Analysis {
+    let mut visits: HashMap<Point, Vec<u64>> = HashMap::new();
+    // The loop erased path so far, and where each of its cells is in it
+    let mut path = vec![Point::origin()];
+    let mut on_path: HashMap<Point, usize> = HashMap::new();
+    on_path.insert(Point::origin(), 0);
+    visits.insert(Point::origin(), vec![0]);
+
+    let mut p = Point::origin();
+    let mut steps = 0;
+    for v in wire {
+        let delta = v.delta();
+        for _ in 0..v.len() {
+            p = p.step(delta);
+            steps += 1;
+            visits.entry(p).or_default().push(steps);
+            match on_path.get(&p) {
+                Some(&i) => {
+                    for cut in path.drain(i + 1..) {
+                        on_path.remove(&cut);
+                    }
+                }
+                None => {
+                    on_path.insert(p, path.len());
+                    path.push(p);
+                }
+            }
+        }
+    }
+
+    let mut self_crossings: Vec<SelfCrossing> = visits
+        .into_iter()
+        .filter(|(_, steps)| steps.len() > 1)
+        .map(|(point, steps)| SelfCrossing { point, steps })
+        .collect();
+    self_crossings.sort_by_key(|c| c.point);
+    let mut loops: Vec<Loop> = self_crossings
+        .iter()
+        .flat_map(|c| {
+            c.steps.windows(2).map(move |w| Loop {
+                point: c.point,
+                start: w[0],
+                end: w[1],
+            })
+        })
+        .collect();
+    loops.sort_by_key(|l| (l.start, l.end));
+    let shortcut = from_polyline(&path)
+        .expect("Every step is orthogonal")
+        .simplified();
+    Analysis {
+        self_crossings,
+        loops,
+        shortcut,
+    }
+}
+
+#[test]
+fn single_loop() {
+    use crate::grid::parse_vector_list;
+    let wire = parse_vector_list("R3,U2,L1,D4");
+    let a = analyze(&wire);
+    assert_eq!(
+        a.self_crossings,
+        [SelfCrossing {
+            point: Point::new(2, 0),
+            steps: vec![2, 8]
+        }]
+    );
+    assert_eq!(a.loops.len(), 1);
+    assert_eq!(a.loops[0].len(), 6);
+    assert_eq!(a.shortcut, parse_vector_list("R2,D2"));
+    assert_eq!(a.saved(&wire), 6);
+    assert_eq!(a.shortcut.end(), wire.end());
+}
+
+#[test]
+fn nested_loops() {
+    use crate::grid::parse_vector_list;
+    // Circles the origin, then retraces part of the way
+    let wire = parse_vector_list("R2,U2,L2,D2,R1,L1,U5");
+    let a = analyze(&wire);
+    assert_eq!(a.self_crossings[0].point, Point::new(0, -2));
+    assert_eq!(a.loops.first().map(|l| (l.start, l.end)), Some((0, 8)));
+    assert_eq!(a.shortcut, parse_vector_list("U5"));
+    assert!(analyze(&parse_vector_list("R8,U5,L5,D3")).loops.is_empty());
+}
@@ -0,0 +1,438 @@
+//! Reading module manifests: one mass per line, `name,mass` CSV, or JSON,
+//! either one `{"name": ..., "mass": ...}` object per line or a whole
+//! document holding an array of them.
+
+use crate::fuel::{Mass, Module};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{BufRead, Lines};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Pick the format from the first record
+    Auto,
+    Lines,
+    Csv,
+    Json,
+}
+
+impl Format {
+    fn detect(line: &str) -> Format {
+        if line.starts_with('{') || line.starts_with('[') {
+            Format::Json
+        } else if line.contains(',') {
+            Format::Csv
+        } else {
+            Format::Lines
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "auto" => Ok(Format::Auto),
+            "lines" => Ok(Format::Lines),
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown manifest format: '{}'", s)),
+        }
+    }
+}
+
+/// What to do with lines that cannot be parsed. Reading always stops at an
+/// I/O error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Stop at the first invalid line
+    Fail,
+    /// Drop invalid lines silently
+    Skip,
+    /// Keep going, but record every invalid line
+    Collect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// A record that cannot be parsed
+    Invalid,
+    /// The input could not be read, nothing more comes after it
+    Io,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number
+    pub line: usize,
+    pub message: String,
+    pub kind: ErrorKind,
+}
+
+impl ParseError {
+    fn invalid(line: usize, message: String) -> ParseError {
+        ParseError {
+            line,
+            message,
+            kind: ErrorKind::Invalid,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Invalid => write!(f, "line {}: {}", self.line, self.message),
+            ErrorKind::Io => write!(f, "line {}: read failed: {}", self.line, self.message),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonModule {
+    name: Option<String>,
+    mass: u64,
+}
+
+/// Streams modules from a manifest, one `Result` per record. Blank lines and
+/// lines starting with `#` are ignored.
+///
+/// JSON objects are read one at a time, and may span several lines. A JSON
+/// array is a whole document, read in full once it is seen.
+pub struct Reader<R> {
+    lines: Lines<R>,
+    line: usize,
+    format: Format,
+    /// Whether a record, or the CSV header, has been seen
+    started: bool,
+    /// Records already parsed, for JSON documents
+    pending: VecDeque<Result<Module, ParseError>>,
+    /// Set after an I/O error
+    done: bool,
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(reader: R, format: Format) -> Reader<R> {
+        Reader {
+            lines: reader.lines(),
+            line: 0,
+            format,
+            started: false,
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    fn parse(&mut self, text: &str) -> Result<Option<Module>, String> {
+        let first = !self.started;
+        self.started = true;
+        match self.format {
+            Format::Lines => Ok(Some(Module::new(parse_mass(text)?))),
+            Format::Csv => {
+                let (name, mass) = text
+                    .rsplit_once(',')
+                    .ok_or_else(|| format!("Expected 'name,mass', got '{}'", text))?;
+                let name = name.trim().trim_matches('"');
+                let mass = mass.trim();
+                if first && mass.eq_ignore_ascii_case("mass") {
+                    // Header
+                    return Ok(None);
+                }
+                Ok(Some(Module {
+                    name: Some(name.to_string()),
+                    mass: parse_mass(mass)?,
+                }))
+            }
+            Format::Json | Format::Auto => unreachable!(),
+        }
+    }
+
+    fn io_error(&mut self, e: std::io::Error) -> ParseError {
+        self.done = true;
+        ParseError {
+            line: self.line,
+            message: e.to_string(),
+            kind: ErrorKind::Io,
+        }
+    }
+
+    /// Reads one JSON object starting at `first`, taking more lines until it
+    /// is complete.
+    fn read_json_object(&mut self, first: &str) -> Result<Module, ParseError> {
+        let first_line = self.line;
+        let mut text = first.to_string();
+        let raw = loop {
+            let e = match serde_json::from_str::<&RawValue>(&text) {
+                Ok(_) => break &text,
+                Err(e) => e,
+            };
+            let line = first_line + e.line().max(1) - 1;
+            if !e.is_eof() {
+                return Err(ParseError::invalid(line, e.to_string()));
+            }
+            match self.lines.next() {
+                Some(Ok(more)) => {
+                    self.line += 1;
+                    text.push('\n');
+                    // Keep the line count, so errors point at the right line
+                    if !more.trim_start().starts_with('#') {
+                        text.push_str(&more);
+                    }
+                }
+                Some(Err(e)) => {
+                    self.line += 1;
+                    return Err(self.io_error(e));
+                }
+                None => return Err(ParseError::invalid(line, e.to_string())),
+            }
+        };
+        json_module(raw)
+            .map_err(|e| ParseError::invalid(first_line + e.line().max(1) - 1, e.to_string()))
+    }
+
+    /// Reads the rest of the input, starting with `first`, as a JSON
+    /// document: values, each an object or an array of them.
+    fn read_json_document(&mut self, first: &str) {
+        let first_line = self.line;
+        let mut text = format!("{}\n", first);
+        let mut io_error = None;
+        while let Some(line) = self.lines.next() {
+            self.line += 1;
+            match line {
+                // Keep the line count, so positions in `text` map to lines
+                Ok(line) if line.trim_start().starts_with('#') => text.push('\n'),
+                Ok(line) => {
+                    text.push_str(&line);
+                    text.push('\n');
+                }
+                Err(e) => {
+                    io_error = Some(self.io_error(e));
+                    break;
+                }
+            }
+        }
+        let line_of = |value: &RawValue| {
+            let offset = value.get().as_ptr() as usize - text.as_ptr() as usize;
+            first_line + text[..offset].matches('\n').count()
+        };
+        let module = |value: &RawValue| {
+            json_module(value.get()).map_err(|e| ParseError::invalid(line_of(value), e.to_string()))
+        };
+        for value in serde_json::Deserializer::from_str(&text).into_iter::<&RawValue>() {
+            match value {
+                Ok(value) if value.get().starts_with('[') => {
+                    match serde_json::from_str::<Vec<&RawValue>>(value.get()) {
+                        Ok(items) => self.pending.extend(items.into_iter().map(module)),
+                        Err(e) => self
+                            .pending
+                            .push_back(Err(ParseError::invalid(line_of(value), e.to_string()))),
+                    }
+                }
+                Ok(value) => self.pending.push_back(module(value)),
+                Err(e) => {
+                    // Nothing after a syntax error can be made sense of
+                    let line = first_line + e.line().max(1) - 1;
+                    self.pending
+                        .push_back(Err(ParseError::invalid(line, e.to_string())));
+                    break;
+                }
+            }
+        }
+        self.pending.extend(io_error.map(Err));
+    }
+}
+
+fn json_module(text: &str) -> serde_json::Result<Module> {
+    serde_json::from_str::<JsonModule>(text).map(|m| Module {
+        name: m.name,
+        mass: Mass(m.mass),
+    })
+}
+
+fn parse_mass(text: &str) -> Result<Mass, String> {
+    text.parse()
+        .map_err(|_| format!("Invalid mass: '{}'", text))
+}
+
+impl<R: BufRead> Iterator for Reader<R> {
+    type Item = Result<Module, ParseError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.pending.pop_front() {
+                return Some(result);
+            }
+            if self.done {
+                return None;
+            }
+            let text = match self.lines.next()? {
+                Ok(text) => text,
+                Err(e) => {
+                    self.line += 1;
+                    return Some(Err(self.io_error(e)));
+                }
+            };
+            self.line += 1;
+            let text = text.trim();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+            if self.format == Format::Auto {
+                self.format = Format::detect(text);
+            }
+            if self.format == Format::Json {
+                if !text.starts_with('[') {
+                    return Some(self.read_json_object(text));
+                }
+                self.read_json_document(text);
+                self.done = true;
+                continue;
+            }
+            match self.parse(text) {
+                Ok(Some(module)) => return Some(Ok(module)),
+                Ok(None) => continue,
+                Err(message) => return Some(Err(ParseError::invalid(self.line, message))),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Manifest {
+    pub modules: Vec<Module>,
+    /// Invalid lines, only filled in with `Policy::Collect`
+    pub errors: Vec<ParseError>,
+}
+
+/// Reads a whole manifest. I/O errors are returned whatever the policy.
+pub fn read<R: BufRead>(reader: R, format: Format, policy: Policy) -> Result<Manifest, ParseError> {
+    let mut manifest = Manifest::default();
+    for result in Reader::new(reader, format) {
+        match result {
+            Ok(module) => manifest.modules.push(module),
+            Err(e) if e.kind == ErrorKind::Io => return Err(e),
+            Err(e) => match policy {
+                Policy::Fail => return Err(e),
+                Policy::Skip => (),
+                Policy::Collect => manifest.errors.push(e),
+            },
+        }
+    }
+    Ok(manifest)
+}
+
+#[test]
+fn read_lines() {
+    let m = read("12\n\n 14 \n".as_bytes(), Format::Auto, Policy::Fail).unwrap();
+    assert_eq!(m.modules, [Module::new(Mass(12)), Module::new(Mass(14))]);
+}
+
+#[test]
+fn read_policies() {
+    let text = "12\nx\n14\n-3\n";
+    assert_eq!(
+        read(text.as_bytes(), Format::Lines, Policy::Fail),
+        Err(ParseError::invalid(2, "Invalid mass: 'x'".to_string()))
+    );
+    let m = read(text.as_bytes(), Format::Lines, Policy::Skip).unwrap();
+    assert_eq!(m.modules.len(), 2);
+    assert!(m.errors.is_empty());
+    let m = read(text.as_bytes(), Format::Lines, Policy::Collect).unwrap();
+    assert_eq!(m.modules.len(), 2);
+    assert_eq!(m.errors.iter().map(|e| e.line).collect::<Vec<_>>(), [2, 4]);
+}
+
+#[test]
+fn read_csv() {
+    let text = "# Modules\n\nname,mass\n\"engine, left\",1969\nhull,12\n";
+    let m = read(text.as_bytes(), Format::Auto, Policy::Fail).unwrap();
+    assert_eq!(
+        m.modules,
+        [
+            Module {
+                name: Some("engine, left".to_string()),
+                mass: Mass(1969)
+            },
+            Module {
+                name: Some("hull".to_string()),
+                mass: Mass(12)
+            }
+        ]
+    );
+}
+
+#[test]
+fn read_json() {
+    let text = "{\"name\": \"hull\", \"mass\": 12}\n{\"mass\": 14}\n{\"name\": 1}\n";
+    let m = read(text.as_bytes(), Format::Auto, Policy::Collect).unwrap();
+    assert_eq!(
+        m.modules,
+        [
+            Module {
+                name: Some("hull".to_string()),
+                mass: Mass(12)
+            },
+            Module::new(Mass(14))
+        ]
+    );
+    assert_eq!(m.errors[0].line, 3);
+}
+
+#[test]
+fn read_json_document() {
+    let text = "# Modules\n[\n  {\"name\": \"hull\", \"mass\": 12},\n  {\n    \"mass\": 14\n  },\n  {\"mass\": \"x\"}\n]\n";
+    let m = read(text.as_bytes(), Format::Auto, Policy::Collect).unwrap();
+    assert_eq!(
+        m.modules.iter().map(|m| m.mass).collect::<Vec<_>>(),
+        [Mass(12), Mass(14)]
+    );
+    assert_eq!(m.errors.iter().map(|e| e.line).collect::<Vec<_>>(), [7]);
+    let m = read(
+        "{\"mass\": 12}\n{\"mass\":".as_bytes(),
+        Format::Json,
+        Policy::Collect,
+    )
+    .unwrap();
+    assert_eq!(m.modules, [Module::new(Mass(12))]);
+    assert_eq!(m.errors.len(), 1);
+}
+
+#[test]
+fn json_lines_stream() {
+    use std::io::{self, Read};
+    /// The same multi-line record, forever
+    struct Endless(usize);
+    impl Read for Endless {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let record = b"{\"mass\":\n 12}\n";
+            for b in buf.iter_mut() {
+                *b = record[self.0 % record.len()];
+                self.0 += 1;
+            }
+            Ok(buf.len())
+        }
+    }
+    let mut reader = Reader::new(io::BufReader::new(Endless(0)), Format::Auto);
+    assert_eq!(reader.nth(2), Some(Ok(Module::new(Mass(12)))));
+    assert_eq!(reader.line, 6);
+}
+
+#[test]
+fn io_errors_end_reading() {
+    use std::io::{self, Read};
+    /// Fails every read, like stdin being a directory
+    struct Broken;
+    impl Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken"))
+        }
+    }
+    let reader = || io::BufReader::new(Broken);
+    assert_eq!(Reader::new(reader(), Format::Auto).count(), 1);
+    for &policy in &[Policy::Fail, Policy::Skip, Policy::Collect] {
+        let e = read(reader(), Format::Auto, policy).unwrap_err();
+        assert_eq!((e.line, e.kind), (1, ErrorKind::Io));
+    }
+}
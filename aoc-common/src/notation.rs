@@ -0,0 +1,338 @@
+//! Parsing wire paths written as `R8,U5,L5,D3`, reporting where and why
+//! a path is malformed instead of panicking.
+//!
+//! Directions may be upper or lower case, and spaces are allowed around
+//! moves and between a direction and its length. Besides `U`, `D`, `L` and
+//! `R`, the compass directions `N`, `S`, `E` and `W` and the diagonals
+//! `UL`, `UR`, `DL`, `DR` (or `NW`, `NE`, `SW`, `SE`) and the moves in
+//! depth `F` and `B` are understood when enabled in the `Notation`.
+
+use crate::grid::{Vector, VectorList};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+    Forward,
+    Back,
+}
+
+impl Direction {
+    /// The single step this direction moves in, `Up` being negative y and
+    /// `Forward` positive z
+    pub fn delta(self) -> (i64, i64, i64) {
+        match self {
+            Direction::Up => (0, -1, 0),
+            Direction::Down => (0, 1, 0),
+            Direction::Left => (-1, 0, 0),
+            Direction::Right => (1, 0, 0),
+            Direction::UpLeft => (-1, -1, 0),
+            Direction::UpRight => (1, -1, 0),
+            Direction::DownLeft => (-1, 1, 0),
+            Direction::DownRight => (1, 1, 0),
+            Direction::Forward => (0, 0, 1),
+            Direction::Back => (0, 0, -1),
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        let (dx, dy, _) = self.delta();
+        dx != 0 && dy != 0
+    }
+
+    pub fn is_depth(self) -> bool {
+        self.delta().2 != 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub direction: Direction,
+    pub len: usize,
+}
+
+/// Which directions besides `U`, `D`, `L` and `R` are accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Notation {
+    /// `N`, `S`, `E` and `W`, north being up
+    pub compass: bool,
+    /// Two letter diagonal directions
+    pub diagonals: bool,
+    /// `F` and `B`, moving forward and back in a third dimension
+    pub depth: bool,
+}
+
+impl Default for Notation {
+    fn default() -> Notation {
+        Notation {
+            compass: true,
+            diagonals: false,
+            depth: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// Nothing between two commas
+    Empty,
+    UnknownDirection(String),
+    MissingLength,
+    BadLength(String),
+    /// A diagonal move where only orthogonal ones are allowed
+    Diagonal,
+    /// A move in depth where only flat ones are allowed
+    Depth,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 0-based index of the offending move
+    pub token: usize,
+    /// Byte offset and length of the offending text in the input
+    pub offset: usize,
+    pub len: usize,
+    pub kind: ErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} at byte {}: ", self.token, self.offset)?;
+        match &self.kind {
+            ErrorKind::Empty => write!(f, "empty move"),
+            ErrorKind::UnknownDirection(d) => write!(f, "unknown direction '{}'", d),
+            ErrorKind::MissingLength => write!(f, "missing length"),
+            ErrorKind::BadLength(l) => write!(f, "invalid length '{}'", l),
+            ErrorKind::Diagonal => write!(f, "diagonal moves are not allowed here"),
+            ErrorKind::Depth => write!(f, "moves in depth are not allowed here"),
+        }
+    }
+}
+
+impl ParseError {
+    /// `line` with the offending text underlined and the error below it
+    pub fn render(&self, line: &str) -> String {
+        let indent = line[..self.offset.min(line.len())].chars().count();
+        format!(
+            "{}\n{}{} {}",
+            line,
+            " ".repeat(indent),
+            "^".repeat(self.len.max(1)),
+            self
+        )
+    }
+}
+
+fn direction(text: &str, notation: Notation) -> Option<Direction> {
+    let (d, compass) = match text.to_ascii_uppercase().as_str() {
+        "U" => (Direction::Up, false),
+        "D" => (Direction::Down, false),
+        "L" => (Direction::Left, false),
+        "R" => (Direction::Right, false),
+        "N" => (Direction::Up, true),
+        "S" => (Direction::Down, true),
+        "W" => (Direction::Left, true),
+        "E" => (Direction::Right, true),
+        "UL" => (Direction::UpLeft, false),
+        "UR" => (Direction::UpRight, false),
+        "DL" => (Direction::DownLeft, false),
+        "DR" => (Direction::DownRight, false),
+        "NW" => (Direction::UpLeft, true),
+        "NE" => (Direction::UpRight, true),
+        "SW" => (Direction::DownLeft, true),
+        "SE" => (Direction::DownRight, true),
+        "F" => (Direction::Forward, false),
+        "B" => (Direction::Back, false),
+        _ => return None,
+    };
+    if (compass && !notation.compass)
+        || (d.is_diagonal() && !notation.diagonals)
+        || (d.is_depth() && !notation.depth)
+    {
+        None
+    } else {
+        Some(d)
+    }
+}
+
+/// Parses one move; `offset` is where `text` starts in the line.
+fn parse_move(
+    text: &str,
+    token: usize,
+    offset: usize,
+    notation: Notation,
+) -> Result<Move, ParseError> {
+    let error = |start: usize, len: usize, kind| ParseError {
+        token,
+        offset: offset + start,
+        len,
+        kind,
+    };
+    let start = text.len() - text.trim_start().len();
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Err(error(0, text.len(), ErrorKind::Empty));
+    }
+    let letters = trimmed
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(trimmed.len());
+    let dir = &trimmed[..letters];
+    let direction = direction(dir, notation).ok_or_else(|| {
+        let len = dir
+            .len()
+            .max(trimmed.chars().next().map_or(1, char::len_utf8));
+        error(
+            start,
+            len,
+            ErrorKind::UnknownDirection(trimmed[..len].to_string()),
+        )
+    })?;
+    let rest = &trimmed[letters..];
+    let len_start = start + letters + (rest.len() - rest.trim_start().len());
+    let len = rest.trim_start();
+    if len.is_empty() {
+        return Err(error(start, letters, ErrorKind::MissingLength));
+    }
+    let len = len
+        .parse()
+        .map_err(|_| error(len_start, len.len(), ErrorKind::BadLength(len.to_string())))?;
+    Ok(Move { direction, len })
+}
+
+/// Parses a whole wire, moves separated by commas. An empty or blank line is
+/// a wire that never leaves the origin.
+pub fn parse_moves(line: &str, notation: Notation) -> Result<Vec<Move>, ParseError> {
+    if line.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut offset = 0;
+    let mut moves = Vec::new();
+    for (token, text) in line.split(',').enumerate() {
+        moves.push(parse_move(text, token, offset, notation)?);
+        offset += text.len() + 1;
+    }
+    Ok(moves)
+}
+
+/// Parses a flat orthogonal wire, rejecting diagonal moves and moves in
+/// depth.
+pub fn parse_wire(line: &str) -> Result<VectorList, ParseError> {
+    let notation = Notation {
+        compass: true,
+        diagonals: true,
+        depth: true,
+    };
+    let moves = parse_moves(line, notation)?;
+    let mut offset = 0;
+    let mut vl = VectorList::new();
+    for ((token, text), m) in line.split(',').enumerate().zip(moves) {
+        vl.push(match m.direction {
+            Direction::Up => Vector::Up(m.len),
+            Direction::Down => Vector::Down(m.len),
+            Direction::Left => Vector::Left(m.len),
+            Direction::Right => Vector::Right(m.len),
+            d => {
+                let start = text.len() - text.trim_start().len();
+                return Err(ParseError {
+                    token,
+                    offset: offset + start,
+                    len: text.trim().len(),
+                    kind: if d.is_depth() {
+                        ErrorKind::Depth
+                    } else {
+                        ErrorKind::Diagonal
+                    },
+                });
+            }
+        });
+        offset += text.len() + 1;
+    }
+    Ok(vl)
+}
+
+#[test]
+fn lenient_input() {
+    assert_eq!(
+        parse_wire(" r8, U 5 ,l0,d3 ").unwrap(),
+        [
+            Vector::Right(8),
+            Vector::Up(5),
+            Vector::Left(0),
+            Vector::Down(3)
+        ]
+    );
+    assert_eq!(
+        parse_wire("N2,e3,S1,W4").unwrap(),
+        [
+            Vector::Up(2),
+            Vector::Right(3),
+            Vector::Down(1),
+            Vector::Left(4)
+        ]
+    );
+    assert_eq!(parse_wire("").unwrap(), []);
+}
+
+#[test]
+fn diagonals() {
+    let all = Notation {
+        compass: true,
+        diagonals: true,
+        depth: true,
+    };
+    let moves = parse_moves("ne3,DL2", all).unwrap();
+    assert_eq!(
+        moves,
+        [
+            Move {
+                direction: Direction::UpRight,
+                len: 3
+            },
+            Move {
+                direction: Direction::DownLeft,
+                len: 2
+            }
+        ]
+    );
+    let e = parse_moves("R1,UR2", Notation::default()).unwrap_err();
+    assert_eq!(e.kind, ErrorKind::UnknownDirection("UR".to_string()));
+    let e = parse_wire("R1, UR2").unwrap_err();
+    assert_eq!(
+        (e.token, e.offset, e.len, e.kind),
+        (1, 4, 3, ErrorKind::Diagonal)
+    );
+}
+
+#[test]
+fn errors() {
+    let e = parse_wire("R8,U5,X3,D3").unwrap_err();
+    assert_eq!((e.token, e.offset, e.len), (2, 6, 1));
+    assert_eq!(e.kind, ErrorKind::UnknownDirection("X".to_string()));
+    assert_eq!(
+        e.render("R8,U5,X3,D3"),
+        "R8,U5,X3,D3\n      ^ move 2 at byte 6: unknown direction 'X'"
+    );
+    let e = parse_wire("R8,,U5").unwrap_err();
+    assert_eq!((e.token, e.offset, e.kind), (1, 3, ErrorKind::Empty));
+    let e = parse_wire("R8,U").unwrap_err();
+    assert_eq!(
+        (e.token, e.offset, e.kind),
+        (1, 3, ErrorKind::MissingLength)
+    );
+    let e = parse_wire("R8,U 5x").unwrap_err();
+    assert_eq!(
+        (e.token, e.offset, e.len, e.kind),
+        (1, 5, 2, ErrorKind::BadLength("5x".to_string()))
+    );
+    let e = parse_wire("R-3").unwrap_err();
+    assert_eq!(e.kind, ErrorKind::BadLength("-3".to_string()));
+    let e = parse_wire("7").unwrap_err();
+    assert_eq!(e.kind, ErrorKind::UnknownDirection("7".to_string()));
+}
@@ -0,0 +1,435 @@
+//! Password rules, and iterating over the numbers in a range passing them.
+
+/// A check a candidate password must pass. Candidates are given both as a
+/// number and as its decimal digits.
+pub trait Rule {
+    fn check(&self, n: u64, digits: &str) -> bool;
+
+    fn and<R: Rule>(self, other: R) -> And<Self, R>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+
+    fn or<R: Rule>(self, other: R) -> Or<Self, R>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self)
+    }
+}
+
+pub struct And<A, B>(pub A, pub B);
+pub struct Or<A, B>(pub A, pub B);
+pub struct Not<A>(pub A);
+
+impl<A: Rule, B: Rule> Rule for And<A, B> {
+    fn check(&self, n: u64, digits: &str) -> bool {
+        self.0.check(n, digits) && self.1.check(n, digits)
+    }
+}
+
+impl<A: Rule, B: Rule> Rule for Or<A, B> {
+    fn check(&self, n: u64, digits: &str) -> bool {
+        self.0.check(n, digits) || self.1.check(n, digits)
+    }
+}
+
+impl<A: Rule> Rule for Not<A> {
+    fn check(&self, n: u64, digits: &str) -> bool {
+        !self.0.check(n, digits)
+    }
+}
+
+impl<R: Rule + ?Sized> Rule for Box<R> {
+    fn check(&self, n: u64, digits: &str) -> bool {
+        (**self).check(n, digits)
+    }
+}
+
+/// Checks on the digits alone, like the original puzzle rules
+impl Rule for fn(&str) -> bool {
+    fn check(&self, _: u64, digits: &str) -> bool {
+        self(digits)
+    }
+}
+
+/// Any check on the number and its digits
+pub struct Custom<F>(pub F);
+
+impl<F: Fn(u64, &str) -> bool> Rule for Custom<F> {
+    fn check(&self, n: u64, digits: &str) -> bool {
+        (self.0)(n, digits)
+    }
+}
+
+/// Number of digits, both bounds included
+pub struct Length {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl Length {
+    pub fn exactly(len: usize) -> Length {
+        Length { min: len, max: len }
+    }
+}
+
+impl Rule for Length {
+    fn check(&self, _: u64, digits: &str) -> bool {
+        self.min <= digits.len() && digits.len() <= self.max
+    }
+}
+
+/// The value itself, both bounds included
+pub struct Range {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl Rule for Range {
+    fn check(&self, n: u64, _: &str) -> bool {
+        self.start <= n && n <= self.end
+    }
+}
+
+/// How each digit compares to the one before it, going left to right
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Monotonic {
+    NonDecreasing,
+    Increasing,
+    NonIncreasing,
+    Decreasing,
+}
+
+impl Rule for Monotonic {
+    fn check(&self, _: u64, digits: &str) -> bool {
+        let d = digits.as_bytes();
+        d.windows(2).all(|w| match self {
+            Monotonic::NonDecreasing => w[0] <= w[1],
+            Monotonic::Increasing => w[0] < w[1],
+            Monotonic::NonIncreasing => w[0] >= w[1],
+            Monotonic::Decreasing => w[0] > w[1],
+        })
+    }
+}
+
+/// Some run of one repeated digit is between `min` and `max` digits long
+pub struct Runs {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl Runs {
+    pub fn at_least(min: usize) -> Runs {
+        Runs {
+            min,
+            max: usize::MAX,
+        }
+    }
+
+    pub fn exactly(len: usize) -> Runs {
+        Runs { min: len, max: len }
+    }
+}
+
+impl Rule for Runs {
+    fn check(&self, _: u64, digits: &str) -> bool {
+        let d = digits.as_bytes();
+        let mut start = 0;
+        for i in 1..=d.len() {
+            if i == d.len() || d[i] != d[start] {
+                if self.allows(i - start) {
+                    return true;
+                }
+                start = i;
+            }
+        }
+        false
+    }
+}
+
+/// None of these digits appear
+pub struct Forbidden(pub Vec<u8>);
+
+impl Rule for Forbidden {
+    fn check(&self, _: u64, digits: &str) -> bool {
+        digits.bytes().all(|b| !self.0.contains(&(b - b'0')))
+    }
+}
+
+/// Iterates over the numbers in `(current, stop]` that pass `rule`.
+pub struct PasswordIterator<R> {
+    pub current: u64,
+    pub stop: u64,
+    pub rule: R,
+}
+
+impl<R: Rule> Iterator for PasswordIterator<R> {
+    type Item = u64;
+    fn next(&mut self) -> Option<u64> {
+        while self.current < self.stop {
+            self.current += 1;
+            if self.rule.check(self.current, &self.current.to_string()) {
+                return Some(self.current);
+            }
+        }
+        None
+    }
+}
+
+/// Iterates over the numbers in `(current, stop]` whose digits never
+/// decrease, jumping over the ones that do instead of checking them.
+pub struct Sorted {
+    pub current: u64,
+    pub stop: u64,
+}
+
+impl Iterator for Sorted {
+    type Item = u64;
+    fn next(&mut self) -> Option<u64> {
+        if self.current >= self.stop {
+            return None;
+        }
+        // The smallest sorted number above `current`: at the first digit
+        // smaller than the one before it, repeat the one before it
+        let mut d = (self.current + 1).to_string().into_bytes();
+        if let Some(i) = (1..d.len()).find(|&i| d[i] < d[i - 1]) {
+            let fill = d[i - 1];
+            d[i..].iter_mut().for_each(|b| *b = fill);
+        }
+        // Only fails past `u64::MAX`, which is past `stop` too
+        let next = std::str::from_utf8(&d).ok()?.parse().ok()?;
+        self.current = next;
+        if next <= self.stop {
+            Some(next)
+        } else {
+            None
+        }
+    }
+}
+
+/// Counts the numbers in `(start, stop]` passing `rule`, checking only the
+/// ones whose digits never decrease, so `rule` must imply that.
+pub fn count_sorted<R: Rule>(start: u64, stop: u64, rule: &R) -> u64 {
+    Sorted {
+        current: start,
+        stop,
+    }
+    .filter(|&n| rule.check(n, &n.to_string()))
+    .count() as u64
+}
+
+/// Counts the numbers in `(start, stop]` whose digits never decrease and
+/// that have a run allowed by `runs`, without going through them. Bounds
+/// may have up to 39 digits.
+///
+/// A sorted number is just how many times each digit appears, so this adds
+/// up the ways of choosing those counts, digit by digit.
+pub fn count(start: u128, stop: u128, runs: &Runs) -> u64 {
+    let counter = RunCounter::new(runs);
+    counter.up_to(stop).saturating_sub(counter.up_to(start))
+}
+
+/// The digits of a `u128`, at most 39 of them
+const DIGITS: usize = 39;
+
+struct RunCounter<'a> {
+    runs: &'a Runs,
+    /// `ways[d][len][found]`: ways to fill `len` more places using digits
+    /// `d` to 9 in order, ending up with an allowed run, `found` telling
+    /// whether there already is one
+    ways: Vec<[[u64; 2]; DIGITS + 1]>,
+}
+
+impl<'a> RunCounter<'a> {
+    fn new(runs: &'a Runs) -> RunCounter<'a> {
+        let mut ways = vec![[[0; 2]; DIGITS + 1]; 11];
+        ways[10][0][1] = 1;
+        for d in (1..10).rev() {
+            for len in 0..=DIGITS {
+                for found in 0..2 {
+                    ways[d][len][found] = (0..=len)
+                        .map(|c| {
+                            let found = found == 1 || (c > 0 && runs.allows(c));
+                            ways[d + 1][len - c][found as usize]
+                        })
+                        .sum();
+                }
+            }
+        }
+        RunCounter { runs, ways }
+    }
+
+    /// Ways to fill `len` more places after a run of `run` digits `d`
+    fn after(&self, d: usize, run: usize, found: bool, len: usize) -> u64 {
+        (0..=len)
+            .map(|more| {
+                let found = found || self.runs.allows(run + more);
+                self.ways[d + 1][len - more][found as usize]
+            })
+            .sum()
+    }
+
+    /// Counted numbers from 1 to `n`
+    fn up_to(&self, n: u128) -> u64 {
+        let digits: Vec<usize> = n.to_string().bytes().map(|b| (b - b'0') as usize).collect();
+        // Anything shorter than `n`
+        let mut total: u64 = (1..digits.len()).map(|len| self.ways[1][len][0]).sum();
+        // As long as `n`, agreeing with it up to some digit and smaller there
+        let (mut prev, mut run, mut found) = (1, 0, false);
+        for (i, &limit) in digits.iter().enumerate() {
+            let rest = digits.len() - i - 1;
+            for d in prev..limit {
+                total += if d == prev && run > 0 {
+                    self.after(d, run + 1, found, rest)
+                } else {
+                    self.after(d, 1, found || (run > 0 && self.runs.allows(run)), rest)
+                };
+            }
+            if limit < prev {
+                return total;
+            }
+            if limit == prev && run > 0 {
+                run += 1;
+            } else {
+                found = found || (run > 0 && self.runs.allows(run));
+                prev = limit;
+                run = 1;
+            }
+        }
+        // And `n` itself, being sorted
+        if found || self.runs.allows(run) {
+            total += 1;
+        }
+        total
+    }
+}
+
+impl Runs {
+    fn allows(&self, len: usize) -> bool {
+        self.min <= len && len <= self.max
+    }
+}
+
+#[test]
+fn built_in_rules() {
+    let check = |r: &dyn Rule, n: u64| r.check(n, &n.to_string());
+    assert!(check(&Length::exactly(6), 123456));
+    assert!(!check(&Length { min: 1, max: 5 }, 123456));
+    assert!(check(&Range { start: 5, end: 9 }, 9));
+    assert!(!check(&Range { start: 5, end: 9 }, 10));
+    assert!(check(&Monotonic::NonDecreasing, 111123));
+    assert!(!check(&Monotonic::Increasing, 111123));
+    assert!(check(&Monotonic::Decreasing, 9530));
+    assert!(check(&Runs::exactly(2), 111122));
+    assert!(!check(&Runs::exactly(2), 123444));
+    assert!(check(&Runs { min: 3, max: 4 }, 123444));
+    assert!(!check(&Forbidden(vec![0, 7]), 1070));
+    assert!(check(&Custom(|n, _: &str| n % 7 == 0), 343));
+}
+
+#[test]
+fn combinators() {
+    let rule = Monotonic::NonDecreasing
+        .and(Runs::at_least(2))
+        .and(Runs::exactly(3).not().or(Forbidden(vec![9]).not()));
+    let check = |n: u64| rule.check(n, &n.to_string());
+    // A digit three times in a row only goes with a 9 somewhere
+    assert!(check(111999));
+    assert!(!check(111234));
+    assert!(check(112233));
+    assert!(!check(123456));
+    let boxed: Vec<Box<dyn Rule>> = vec![Box::new(Length::exactly(2)), Box::new(Runs::exactly(2))];
+    let all = |n: u64| boxed.iter().all(|r| r.check(n, &n.to_string()));
+    assert!(all(11) && !all(12));
+}
+
+#[test]
+fn iterate() {
+    let passwords = PasswordIterator {
+        current: 100,
+        stop: 130,
+        rule: Monotonic::NonDecreasing.and(Runs::at_least(2)),
+    };
+    assert_eq!(
+        passwords.collect::<Vec<_>>(),
+        [111, 112, 113, 114, 115, 116, 117, 118, 119, 122]
+    );
+}
+
+#[test]
+fn sorted_numbers() {
+    let sorted = Sorted {
+        current: 85,
+        stop: 124,
+    };
+    assert_eq!(
+        sorted.collect::<Vec<_>>(),
+        [88, 89, 99, 111, 112, 113, 114, 115, 116, 117, 118, 119, 122, 123, 124]
+    );
+    // Sorted numbers of up to 10 digits are multisets of the digits 1 to 9
+    let all = Runs::at_least(1);
+    assert_eq!(
+        count_sorted(0, 9_999_999_999, &Monotonic::NonDecreasing),
+        92377
+    );
+    assert_eq!(count(0, 9_999_999_999, &all), 92377);
+    assert_eq!(count(0, 9_999_999_999_999_999_999, &all), 6906899);
+    let huge = Sorted {
+        current: u64::MAX - 1,
+        stop: u64::MAX,
+    };
+    assert_eq!(huge.count(), 0);
+}
+
+#[test]
+fn counting() {
+    let pair = Monotonic::NonDecreasing.and(Runs::at_least(2));
+    let exact = Monotonic::NonDecreasing.and(Runs::exactly(2));
+    assert_eq!(count(234208, 765869, &Runs::at_least(2)), 1246);
+    assert_eq!(count(234208, 765869, &Runs::exactly(2)), 814);
+    assert_eq!(count_sorted(234208, 765869, &pair), 1246);
+    assert_eq!(count_sorted(234208, 765869, &exact), 814);
+    assert_eq!(count(765869, 234208, &Runs::exactly(2)), 0);
+    let (start, stop) = (123_456_789_012, 899_999_999_999);
+    assert_eq!(
+        count(start.into(), stop.into(), &Runs::exactly(3)),
+        count_sorted(start, stop, &Monotonic::NonDecreasing.and(Runs::exactly(3)))
+    );
+    // Too many to go through one by one, and past `u64`. Sorted numbers of
+    // 20 digits are multisets of 20 of the digits 1 to 9.
+    let twenty = (10u128.pow(19) - 1, 10u128.pow(20) - 1);
+    assert_eq!(count(twenty.0, twenty.1, &Runs::at_least(1)), 3108105);
+    let long = count(twenty.0, twenty.1, &Runs::exactly(2));
+    assert!(long > 0 && long < 3108105);
+    assert!(count(0, u128::MAX, &Runs::exactly(2)) > count(0, twenty.1, &Runs::exactly(2)));
+}
+
+#[cfg(test)]
+use proptest::prelude::*;
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn count_matches_iteration(start in 0..200_000u64, width in 0..20_000u64, min in 1..4usize, extra in 0..3usize) {
+        let runs = Runs { min, max: min + extra };
+        let stop = start + width;
+        let expected = PasswordIterator {
+            current: start,
+            stop,
+            rule: Monotonic::NonDecreasing.and(Runs { min, max: min + extra }),
+        }
+        .count() as u64;
+        prop_assert_eq!(count(start.into(), stop.into(), &runs), expected);
+        prop_assert_eq!(count_sorted(start, stop, &Monotonic::NonDecreasing.and(runs)), expected);
+    }
+}
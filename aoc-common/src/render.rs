@@ -0,0 +1,409 @@
+//! SVG and PNG pictures of wire layouts of any size, scaled to fit, and GIF
+//! animations of the wires being laid out.
+
+use crate::grid::{BoundingBox, Point, VectorList};
+use crate::segment::{cross, extents, overlaps, segments, Segment};
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+/// Wire colors, reused when there are more wires than colors
+const COLORS: [[u8; 3]; 6] = [
+    [0x1f, 0x77, 0xb4],
+    [0xff, 0x7f, 0x0e],
+    [0x2c, 0xa0, 0x2c],
+    [0x94, 0x67, 0xbd],
+    [0x8c, 0x56, 0x4b],
+    [0x17, 0xbe, 0xcf],
+];
+const BACKGROUND: [u8; 3] = [0xff, 0xff, 0xff];
+const ORIGIN: [u8; 3] = [0x00, 0x00, 0x00];
+const CROSSING: [u8; 3] = [0x55, 0x55, 0x55];
+const HIGHLIGHT: [u8; 3] = [0xd6, 0x27, 0x28];
+const MARGIN: f64 = 10.0;
+
+/// Colors in the order of their palette indices, wire colors last
+const PALETTE: [[u8; 3]; 4 + COLORS.len()] = [
+    BACKGROUND, ORIGIN, CROSSING, HIGHLIGHT, COLORS[0], COLORS[1], COLORS[2], COLORS[3], COLORS[4],
+    COLORS[5],
+];
+
+pub struct Diagram {
+    wires: Vec<Vec<Segment>>,
+    /// Crossings, and the step by which all wires meeting there have reached it
+    crossings: Vec<(Point, u64)>,
+    /// Where two wires run along each other, as the pieces of both
+    overlaps: Vec<(Segment, Segment)>,
+    highlight: Option<Point>,
+    bounds: BoundingBox,
+}
+
+impl Diagram {
+    /// A diagram of `wires`, with every crossing between any two of them
+    /// dotted, the stretches where they overlap traced over, and
+    /// `highlight` marked out
+    pub fn new(wires: &[VectorList], highlight: Option<Point>) -> Diagram {
+        let wires: Vec<Vec<Segment>> = wires.iter().map(segments).collect();
+        let mut crossings: Vec<(Point, u64)> = Vec::new();
+        let mut overlapping = Vec::new();
+        for (i, a) in wires.iter().enumerate() {
+            for b in &wires[i + 1..] {
+                let found = cross(a, b).into_iter();
+                crossings.extend(found.map(|x| (x.point, x.steps.0.max(x.steps.1))));
+                overlapping.extend(overlaps(a, b));
+            }
+        }
+        // Keep the first time each crossing appears
+        crossings.sort();
+        crossings.dedup_by_key(|&mut (p, _)| p);
+        let all: Vec<Segment> = wires.iter().flatten().cloned().collect();
+        let bounds = extents(&all);
+        Diagram {
+            wires,
+            crossings,
+            overlaps: overlapping,
+            highlight,
+            bounds,
+        }
+    }
+
+    /// Scale factor making the layout fit a `size` pixel square
+    fn scale(&self, size: u32) -> f64 {
+        // Spans between the outermost cells, which sit on the margins
+        let span = (self.bounds.width().max(self.bounds.height()) - 1).max(1);
+        (f64::from(size) - 2.0 * MARGIN).max(1.0) / span as f64
+    }
+
+    /// Image size for a layout scaled to fit `size` pixels, keeping its shape
+    fn dimensions(&self, size: u32) -> (u32, u32) {
+        let scale = self.scale(size);
+        let side = |cells: u64| ((cells - 1) as f64 * scale).round() as u32 + 2 * MARGIN as u32 + 1;
+        (side(self.bounds.width()), side(self.bounds.height()))
+    }
+
+    fn project(&self, p: Point, scale: f64) -> (f64, f64) {
+        (
+            MARGIN + (p.x - self.bounds.min.x) as f64 * scale,
+            MARGIN + (p.y - self.bounds.min.y) as f64 * scale,
+        )
+    }
+
+    /// An SVG picture at most `size` pixels wide and high
+    pub fn to_svg(&self, size: u32) -> String {
+        let scale = self.scale(size);
+        let (width, height) = self.dimensions(size);
+        let mut svg = String::new();
+        let mut out = |s: String| svg.push_str(&s);
+        out(format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+            width, height, width, height
+        ));
+        out(format!(
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+            hex(BACKGROUND)
+        ));
+        for (i, wire) in self.wires.iter().enumerate() {
+            let mut points = String::new();
+            let start = std::iter::once(Point::origin());
+            for p in start.chain(wire.iter().map(|s| s.end)) {
+                let (x, y) = self.project(p, scale);
+                write!(points, "{:.1},{:.1} ", x, y).expect("Writing to a String cannot fail");
+            }
+            out(format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1\"/>\n",
+                points.trim_end(),
+                hex(COLORS[i % COLORS.len()])
+            ));
+        }
+        for (from, to) in self.shared(u64::MAX) {
+            let (x1, y1) = self.project(from, scale);
+            let (x2, y2) = self.project(to, scale);
+            out(format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"3\" stroke-linecap=\"round\"/>\n",
+                x1,
+                y1,
+                x2,
+                y2,
+                hex(CROSSING)
+            ));
+        }
+        let mut circle = |p: Point, r: f64, color: [u8; 3]| {
+            let (x, y) = self.project(p, scale);
+            out(format!(
+                "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"{}\"/>\n",
+                x,
+                y,
+                r,
+                hex(color)
+            ));
+        };
+        for &(p, _) in &self.crossings {
+            circle(p, 1.5, CROSSING);
+        }
+        circle(Point::origin(), 4.0, ORIGIN);
+        if let Some(p) = self.highlight {
+            circle(p, 4.0, HIGHLIGHT);
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// The stretches of overlap every wire running along them has reached
+    /// after `step` steps, from their smallest to their largest point
+    fn shared(&self, step: u64) -> Vec<(Point, Point)> {
+        let reached = |s: &Segment| {
+            let end = s.point_at(step.checked_sub(s.steps)?);
+            Some((s.start.min(end), s.start.max(end)))
+        };
+        self.overlaps
+            .iter()
+            .filter_map(|(a, b)| {
+                let ((a1, a2), (b1, b2)) = (reached(a)?, reached(b)?);
+                let (from, to) = (a1.max(b1), a2.min(b2));
+                Some((from, to)).filter(|_| from <= to)
+            })
+            .collect()
+    }
+
+    /// The step by which all wires meeting at `p` have reached it, if it is
+    /// a crossing
+    fn appears(&self, p: Point) -> Option<u64> {
+        let crossed = self
+            .crossings
+            .iter()
+            .filter(|&&(c, _)| c == p)
+            .map(|&(_, s)| s);
+        let overlapped = self.overlaps.iter().filter_map(|(a, b)| {
+            let on = |s: &Segment| s.start.min(s.end) <= p && p <= s.start.max(s.end);
+            Some(a.steps_to(p).max(b.steps_to(p))).filter(|_| on(a))
+        });
+        crossed.chain(overlapped).min()
+    }
+
+    /// Steps the longest wire takes, after which the layout is complete
+    pub fn steps(&self) -> u64 {
+        self.wires
+            .iter()
+            .filter_map(|w| w.last())
+            .map(|s| s.steps + s.start.manhattan_to(s.end))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Palette indices of the layout as it is after every wire has taken
+    /// `step` steps, along with the width and height of the picture
+    fn paint(&self, size: u32, step: u64) -> (Vec<u8>, u32, u32) {
+        let scale = self.scale(size);
+        let (width, height) = self.dimensions(size);
+        let mut pixels = vec![0; (width * height) as usize];
+        let mut plot = |x: i64, y: i64, color: u8| {
+            if x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height {
+                pixels[y as usize * width as usize + x as usize] = color;
+            }
+        };
+        let pixel = |p: Point| {
+            let (x, y) = self.project(p, scale);
+            (x.round() as i64, y.round() as i64)
+        };
+        for (i, wire) in self.wires.iter().enumerate() {
+            let color = (4 + i % COLORS.len()) as u8;
+            for s in wire.iter().filter(|s| s.steps < step) {
+                // Segments are axis aligned, so are their pictures
+                let (x1, y1) = pixel(s.start);
+                let (x2, y2) = pixel(s.point_at(step - s.steps));
+                for x in x1.min(x2)..=x1.max(x2) {
+                    for y in y1.min(y2)..=y1.max(y2) {
+                        plot(x, y, color);
+                    }
+                }
+            }
+        }
+        for (from, to) in self.shared(step) {
+            let ((x1, y1), (x2, y2)) = (pixel(from), pixel(to));
+            for x in x1 - 1..=x2 + 1 {
+                for y in y1 - 1..=y2 + 1 {
+                    plot(x, y, 2);
+                }
+            }
+        }
+        let mut dot = |p: Point, r: i64, color: u8| {
+            let (cx, cy) = pixel(p);
+            for x in cx - r..=cx + r {
+                for y in cy - r..=cy + r {
+                    if (x - cx).pow(2) + (y - cy).pow(2) <= r * r {
+                        plot(x, y, color);
+                    }
+                }
+            }
+        };
+        for &(p, _) in self.crossings.iter().filter(|&&(_, a)| a <= step) {
+            dot(p, 1, 2);
+        }
+        let reached = |p: Point| self.appears(p).is_none_or(|appears| appears <= step);
+        dot(Point::origin(), 4, 1);
+        if let Some(p) = self.highlight.filter(|&p| reached(p)) {
+            dot(p, 4, 3);
+        }
+        (pixels, width, height)
+    }
+
+    /// RGB pixels of a picture at most `size` pixels wide and high, along
+    /// with its width and height
+    pub fn to_pixels(&self, size: u32) -> (Vec<u8>, u32, u32) {
+        self.frame_pixels(size, u64::MAX)
+    }
+
+    /// Like `to_pixels`, but only as far as every wire gets in `step` steps
+    pub fn frame_pixels(&self, size: u32, step: u64) -> (Vec<u8>, u32, u32) {
+        let (indices, width, height) = self.paint(size, step);
+        let pixels = indices.iter().flat_map(|&i| PALETTE[i as usize]).collect();
+        (pixels, width, height)
+    }
+
+    /// An animated GIF of the wires being laid out in `frames` frames,
+    /// `delay` hundredths of a second apart, holding the complete layout
+    /// at the end
+    pub fn to_gif(&self, size: u32, frames: u64, delay: u16) -> Vec<u8> {
+        let (_, width, height) = self.paint(size, 0);
+        let to_u16 = |n: u32| u16::try_from(n).expect("Picture too large for a GIF");
+        let palette: Vec<u8> = PALETTE.iter().flatten().cloned().collect();
+        let mut gif = Vec::new();
+        {
+            let mut encoder = gif::Encoder::new(&mut gif, to_u16(width), to_u16(height), &palette)
+                .expect("GIF encoding into memory failed");
+            encoder
+                .set_repeat(gif::Repeat::Infinite)
+                .expect("GIF encoding into memory failed");
+            let total = self.steps();
+            let frames = frames.max(1);
+            for f in 1..=frames {
+                let step = if f == frames {
+                    u64::MAX
+                } else {
+                    frame_step(total, f, frames)
+                };
+                let (indices, _, _) = self.paint(size, step);
+                let mut frame =
+                    gif::Frame::from_indexed_pixels(to_u16(width), to_u16(height), indices, None);
+                frame.delay = if f == frames {
+                    delay.saturating_mul(10)
+                } else {
+                    delay
+                };
+                encoder
+                    .write_frame(&frame)
+                    .expect("GIF encoding into memory failed");
+            }
+        }
+        gif
+    }
+
+    /// A PNG picture at most `size` pixels wide and high
+    pub fn to_png(&self, size: u32) -> Vec<u8> {
+        let (pixels, width, height) = self.to_pixels(size);
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .expect("PNG encoding into memory failed");
+        png
+    }
+}
+
+/// Steps drawn by frame `f` of `frames`, spreading `total` steps evenly
+fn frame_step(total: u64, f: u64, frames: u64) -> u64 {
+    (u128::from(total) * u128::from(f) / u128::from(frames)) as u64
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+#[cfg(test)]
+use crate::testing::{wires, REFERENCE};
+
+/// The reference layout with its shortest crossing highlighted
+#[cfg(test)]
+fn reference() -> Diagram {
+    Diagram::new(&wires(&REFERENCE), Some(Point::new(6, -5)))
+}
+
+#[test]
+fn svg_marks_crossings() {
+    let svg = reference().to_svg(100);
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(svg.matches("<polyline").count(), 2);
+    // Two crossings, the origin and the highlight
+    assert_eq!(svg.matches("<circle").count(), 4);
+    assert!(svg.contains(&hex(HIGHLIGHT)));
+}
+
+#[test]
+fn png_fits_size() {
+    let d = reference();
+    let (pixels, width, height) = d.to_pixels(100);
+    // The layout is 8 wide and 7 high
+    assert_eq!((width, height), (101, 91));
+    assert_eq!(pixels.len(), (width * height * 3) as usize);
+    let at = |x: u32, y: u32| {
+        let i = 3 * (y * width + x) as usize;
+        [pixels[i], pixels[i + 1], pixels[i + 2]]
+    };
+    // Origin at (0, 0) is bottom left, the highlight at (6, -5)
+    assert_eq!(at(10, 80), ORIGIN);
+    assert_eq!(at(70, 30), HIGHLIGHT);
+    assert_eq!(at(50, 60), BACKGROUND);
+    assert!(d.to_png(100).starts_with(b"\x89PNG"));
+}
+
+#[test]
+fn frames_grow() {
+    let d = reference();
+    assert_eq!(d.steps(), 21);
+    let count = |step| {
+        let (pixels, _, _) = d.frame_pixels(100, step);
+        pixels.chunks(3).filter(|&c| c != BACKGROUND).count()
+    };
+    assert!(count(0) < count(5));
+    assert!(count(5) < count(21));
+    assert_eq!(count(21), count(u64::MAX));
+    // Both wires reach (6, -5) after 15 steps
+    let at = |step: u64| {
+        let (pixels, width, _) = d.frame_pixels(100, step);
+        let i = 3 * (30 * width + 70) as usize;
+        [pixels[i], pixels[i + 1], pixels[i + 2]]
+    };
+    assert_ne!(at(14), HIGHLIGHT);
+    assert_eq!(at(15), HIGHLIGHT);
+    let gif = d.to_gif(100, 10, u16::MAX);
+    assert!(gif.starts_with(b"GIF89a"));
+    // More frames than steps still shows the wires growing
+    assert_eq!(frame_step(21, 50, 100), 10);
+    assert_eq!(frame_step(u64::MAX, 99, 100), u64::MAX / 100 * 99 + 14);
+}
+
+#[test]
+fn overlaps_are_traced() {
+    let d = Diagram::new(&wires(&["R10", "U1,R2,D1,R8"]), Some(Point::new(6, 0)));
+    assert_eq!(d.crossings, [(Point::new(2, 0), 4)]);
+    assert_eq!(d.shared(u64::MAX), [(Point::new(2, 0), Point::new(10, 0))]);
+    // The second wire gets to x = 2 after 4 steps and on to x = 5 by 7
+    assert_eq!(d.shared(3), []);
+    assert_eq!(d.shared(7), [(Point::new(2, 0), Point::new(5, 0))]);
+    assert_eq!(d.appears(Point::new(6, 0)), Some(8));
+    assert_eq!(d.to_svg(100).matches("<line").count(), 1);
+    // However long the overlap, it is a single line, plus a point where
+    // the wires turn away from each other
+    let d = Diagram::new(&wires(&["R3000000000,U1", "R3000000000,D1"]), None);
+    assert_eq!(d.to_svg(100).matches("<line").count(), 2);
+    assert_eq!(d.to_pixels(100).1, 101);
+}
+
+#[test]
+fn huge_layouts_scale() {
+    let wires = wires(&["R4000000000,U5", "U3,R4000000000,D6"]);
+    let (_, width, height) = Diagram::new(&wires, None).to_pixels(500);
+    assert_eq!((width, height), (501, 21));
+}
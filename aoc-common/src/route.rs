@@ -0,0 +1,102 @@
+//! Routing a new wire between the ends of two existing ones.
+
+use crate::grid::{from_polyline, BoundingBox, Point, VectorList, VectorOps};
+use crate::wire::Pallet;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+const STEPS: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// The shortest orthogonal path from the end of `a` to the end of `b` that
+/// touches neither wire in between, if there is one.
+pub fn rewire(a: &VectorList, b: &VectorList) -> Option<VectorList> {
+    let (start, goal) = (a.end(), b.end());
+    let mut pallet = Pallet::new();
+    pallet.draw(a);
+    pallet.draw(b);
+    // Going around everything is always possible outside the wires' box, so
+    // a shortest path never needs to stray further than that
+    let bounds = pallet.extents();
+    let bounds = BoundingBox::new(
+        Point::new(bounds.min.x - 1, bounds.min.y - 1),
+        Point::new(bounds.max.x + 1, bounds.max.y + 1),
+    );
+
+    // A*, with Manhattan distance as the heuristic
+    let mut came_from: HashMap<Point, Point> = HashMap::new();
+    let mut best: HashMap<Point, u64> = HashMap::new();
+    let mut open = BinaryHeap::new();
+    best.insert(start, 0);
+    open.push(Reverse((start.manhattan_to(goal), 0, start)));
+    while let Some(Reverse((_, steps, p))) = open.pop() {
+        if p == goal {
+            let mut path = vec![p];
+            while let Some(&prev) = came_from.get(path.last().unwrap()) {
+                path.push(prev);
+            }
+            path.reverse();
+            let route = from_polyline(&path).expect("Every step is orthogonal");
+            return Some(route.simplified());
+        }
+        if best.get(&p).is_some_and(|&b| b < steps) {
+            continue;
+        }
+        for &delta in &STEPS {
+            let next = p.step(delta);
+            let free = next == goal || pallet.visits(next) == 0;
+            if !free || !bounds.contains(next) {
+                continue;
+            }
+            if best.get(&next).is_none_or(|&b| steps + 1 < b) {
+                best.insert(next, steps + 1);
+                came_from.insert(next, p);
+                open.push(Reverse((
+                    steps + 1 + next.manhattan_to(goal),
+                    steps + 1,
+                    next,
+                )));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+use crate::testing::{pallet, wires, REFERENCE};
+
+#[cfg(test)]
+fn check_route(first: &str, second: &str, expected_len: usize) {
+    let wires = wires(&[first, second]);
+    let (a, b) = (&wires[0], &wires[1]);
+    let route = rewire(a, b).expect("A route exists");
+    assert_eq!(route.iter().map(|v| v.len()).sum::<usize>(), expected_len);
+    let pallet = pallet(&[first, second]);
+    let cells = route.to_polyline(a.end());
+    assert_eq!(*cells.last().unwrap(), b.end());
+    let mut p = a.end();
+    for v in &route {
+        for _ in 0..v.len() {
+            p = p.step(v.delta());
+            assert!(
+                p == b.end() || pallet.visits(p) == 0,
+                "Route touches a wire"
+            );
+        }
+    }
+}
+
+#[test]
+fn routes_around_wires() {
+    check_route("R2", "U2", 4);
+    check_route(REFERENCE[0], REFERENCE[1], 2);
+    check_route("R3", "R3,U1", 1);
+    // The second wire walls off its end but for a gap at the bottom, and
+    // the first one blocks the way down
+    check_route("U2", "R1,U4,R2,D4", 9);
+}
+
+#[test]
+fn enclosed_end() {
+    let w = wires(&["R1", "U1,R2,D2,L2"]);
+    assert_eq!(rewire(&w[0], &w[1]), None);
+}
@@ -0,0 +1,536 @@
+//! Wire crossings from axis-aligned segments instead of single cells.
+//!
+//! Horizontal segments of one wire are swept left to right against the
+//! vertical segments of the other, and segments on the same line are swept
+//! along it, so finding all crossings takes O((n + k) log n) for n segments
+//! and k crossings, however long the segments are.
+
+use crate::grid::{BoundingBox, Coord, Point, VectorList};
+use crate::notation::{self, ParseError};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+    /// Steps the wire has taken when it reaches `start`
+    pub steps: u64,
+}
+
+impl Segment {
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    /// Steps the wire has taken when it reaches `p`, which must lie on the
+    /// segment
+    pub fn steps_to(&self, p: Point) -> u64 {
+        self.steps + (p.x - self.start.x).unsigned_abs() + (p.y - self.start.y).unsigned_abs()
+    }
+
+    /// The point `steps` steps along the segment, stopping at its end
+    pub fn point_at(&self, steps: u64) -> Point {
+        let len = self.start.manhattan_to(self.end);
+        let t = steps.min(len) as i64;
+        let (dx, dy) = (
+            (self.end.x - self.start.x).signum(),
+            (self.end.y - self.start.y).signum(),
+        );
+        Point::new(self.start.x + dx * t, self.start.y + dy * t)
+    }
+
+    fn x_range(&self) -> (i64, i64) {
+        (self.start.x.min(self.end.x), self.start.x.max(self.end.x))
+    }
+
+    fn y_range(&self) -> (i64, i64) {
+        (self.start.y.min(self.end.y), self.start.y.max(self.end.y))
+    }
+}
+
+/// Splits a wire into its segments, dropping zero-length moves.
+pub fn segments(vl: &VectorList) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut p = Point::origin();
+    let mut steps = 0;
+    for v in vl.iter().filter(|v| !v.is_empty()) {
+        let (dx, dy) = v.delta();
+        let len = v.len() as i64;
+        let end = Point::new(p.x + dx * len, p.y + dy * len);
+        segments.push(Segment {
+            start: p,
+            end,
+            steps,
+        });
+        steps += v.len() as u64;
+        p = end;
+    }
+    segments
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Intersection {
+    pub point: Point,
+    /// Steps each wire has taken to get here
+    pub steps: (u64, u64),
+}
+
+enum Event {
+    Insert(usize),
+    Query(usize),
+    Remove(usize),
+}
+
+/// Crossings of the horizontal segments in `hs` with the vertical ones in
+/// `vs`, found with a sweep line over x.
+fn sweep(hs: &[Segment], vs: &[Segment], found: &mut Vec<(Segment, Segment, Point)>) {
+    let mut events: Vec<(i64, u8, Event)> = Vec::new();
+    for (i, h) in hs.iter().enumerate().filter(|(_, h)| h.is_horizontal()) {
+        let (x1, x2) = h.x_range();
+        events.push((x1, 0, Event::Insert(i)));
+        events.push((x2, 2, Event::Remove(i)));
+    }
+    for (i, v) in vs.iter().enumerate().filter(|(_, v)| !v.is_horizontal()) {
+        events.push((v.start.x, 1, Event::Query(i)));
+    }
+    // Inserts before queries before removes, so touching ends count
+    events.sort_by_key(|&(x, order, _)| (x, order));
+
+    let mut active: BTreeSet<(i64, usize)> = BTreeSet::new();
+    for (x, _, event) in events {
+        match event {
+            Event::Insert(i) => {
+                active.insert((hs[i].start.y, i));
+            }
+            Event::Remove(i) => {
+                active.remove(&(hs[i].start.y, i));
+            }
+            Event::Query(i) => {
+                let v = vs[i];
+                let (y1, y2) = v.y_range();
+                for &(y, j) in active.range((y1, 0)..=(y2, usize::MAX)) {
+                    found.push((hs[j], v, Point::new(x, y)));
+                }
+            }
+        }
+    }
+}
+
+/// Pairs of collinear segments, one from each wire, that overlap.
+///
+/// Segments are grouped by the line they lie on, and each line is swept in
+/// order of where the segments start.
+fn overlapping(a: &[Segment], b: &[Segment]) -> Vec<(Segment, Segment)> {
+    // (horizontal, y or x of the line, start, end, wire, index)
+    let mut spans: Vec<(bool, i64, i64, i64, usize, usize)> = Vec::new();
+    for (wire, segments) in [a, b].iter().enumerate() {
+        for (i, s) in segments.iter().enumerate() {
+            let ((lo, hi), line) = if s.is_horizontal() {
+                (s.x_range(), s.start.y)
+            } else {
+                (s.y_range(), s.start.x)
+            };
+            spans.push((s.is_horizontal(), line, lo, hi, wire, i));
+        }
+    }
+    spans.sort_unstable();
+
+    let mut found = Vec::new();
+    for line in spans.chunk_by(|s, t| (s.0, s.1) == (t.0, t.1)) {
+        // Per wire, the segments seen that may still overlap, by their end
+        let mut active: [BTreeSet<(i64, usize)>; 2] = Default::default();
+        for &(_, _, lo, hi, wire, i) in line {
+            let other = &mut active[1 - wire];
+            while other.first().is_some_and(|&(end, _)| end < lo) {
+                other.pop_first();
+            }
+            for &(_, j) in other.iter() {
+                found.push(if wire == 0 {
+                    (a[i], b[j])
+                } else {
+                    (a[j], b[i])
+                });
+            }
+            active[wire].insert((hi, i));
+        }
+    }
+    found
+}
+
+/// Where two overlapping collinear segments lie on their line
+fn overlap_range(sa: &Segment, sb: &Segment) -> (i64, i64) {
+    let ((a1, a2), (b1, b2)) = if sa.is_horizontal() {
+        (sa.x_range(), sb.x_range())
+    } else {
+        (sa.y_range(), sb.y_range())
+    };
+    (a1.max(b1), a2.min(b2))
+}
+
+/// The points of two overlapping collinear segments that can be optimal for
+/// one of the built-in metrics, or all of them if `all` is set: the ends of
+/// the overlap, the point on it closest to the origin and the point where
+/// both wires have taken the same number of steps, plus their neighbours in
+/// case one of them is the origin.
+fn overlap(sa: &Segment, sb: &Segment, all: bool, found: &mut Vec<(Segment, Segment, Point)>) {
+    let (lo, hi) = overlap_range(sa, sb);
+    if all {
+        found.extend((lo..=hi).map(|c| (*sa, *sb, on_line(sa, c))));
+        return;
+    }
+    let closest = 0.max(lo).min(hi);
+    let mut candidates = vec![lo, lo + 1, hi - 1, hi, closest - 1, closest, closest + 1];
+    if let Some(c) = meeting(sa, sb) {
+        candidates.extend_from_slice(&[c - 1, c, c + 1]);
+    }
+    candidates.retain(|&c| lo <= c && c <= hi);
+    found.extend(candidates.into_iter().map(|c| (*sa, *sb, on_line(sa, c))));
+}
+
+/// The part of `s` between `lo` and `hi` along its line, in the direction
+/// the wire runs
+fn clip(s: &Segment, lo: i64, hi: i64) -> Segment {
+    let (from, to) = (on_line(s, lo), on_line(s, hi));
+    let (start, end) = if s.steps_to(from) <= s.steps_to(to) {
+        (from, to)
+    } else {
+        (to, from)
+    };
+    Segment {
+        start,
+        end,
+        steps: s.steps_to(start),
+    }
+}
+
+/// The point at `c` along the line `s` lies on
+fn on_line(s: &Segment, c: i64) -> Point {
+    if s.is_horizontal() {
+        Point::new(c, s.start.y)
+    } else {
+        Point::new(s.start.x, c)
+    }
+}
+
+/// Where along the line of two collinear segments running in opposite
+/// directions the wires have taken (about) the same number of steps.
+fn meeting(sa: &Segment, sb: &Segment) -> Option<i64> {
+    let axis = |s: &Segment| {
+        if s.is_horizontal() {
+            (s.start.x, (s.end.x - s.start.x).signum())
+        } else {
+            (s.start.y, (s.end.y - s.start.y).signum())
+        }
+    };
+    let (a0, da) = axis(sa);
+    let (b0, db) = axis(sb);
+    if da == db {
+        return None;
+    }
+    // sa.steps + da (c - a0) = sb.steps + db (c - b0), with db = -da
+    let twice = sb.steps as i64 - sa.steps as i64 + da * (a0 + b0);
+    Some((twice * da).div_euclid(2))
+}
+
+/// Points, other than the origin, where the wires `a` and `b` meet. Where
+/// they overlap, only the points that can be best for one of the built-in
+/// metrics are included.
+pub fn intersect(a: &[Segment], b: &[Segment]) -> Vec<Intersection> {
+    intersections(a, b, false)
+}
+
+/// Every cell, other than the origin, where the wires `a` and `b` meet,
+/// however long they overlap.
+pub fn intersect_all(a: &[Segment], b: &[Segment]) -> Vec<Intersection> {
+    intersections(a, b, true)
+}
+
+/// Points, other than the origin, where the wires `a` and `b` cross without
+/// running along each other. Where they overlap is left to `overlaps`.
+pub fn cross(a: &[Segment], b: &[Segment]) -> Vec<Intersection> {
+    let mut found = Vec::new();
+    crossing(a, b, &mut found);
+    to_intersections(found)
+}
+
+/// The pieces of `a` and `b` that run along each other, each clipped to
+/// where they overlap.
+pub fn overlaps(a: &[Segment], b: &[Segment]) -> Vec<(Segment, Segment)> {
+    overlapping(a, b)
+        .iter()
+        .map(|(sa, sb)| {
+            let (lo, hi) = overlap_range(sa, sb);
+            (clip(sa, lo, hi), clip(sb, lo, hi))
+        })
+        .collect()
+}
+
+/// Perpendicular crossings of `a` and `b`, sweeping either way round
+fn crossing(a: &[Segment], b: &[Segment], found: &mut Vec<(Segment, Segment, Point)>) {
+    sweep(a, b, found);
+    let mut swapped = Vec::new();
+    sweep(b, a, &mut swapped);
+    found.extend(swapped.into_iter().map(|(sb, sa, p)| (sa, sb, p)));
+}
+
+fn intersections(a: &[Segment], b: &[Segment], all: bool) -> Vec<Intersection> {
+    let mut found = Vec::new();
+    crossing(a, b, &mut found);
+    for (sa, sb) in overlapping(a, b) {
+        overlap(&sa, &sb, all, &mut found);
+    }
+    to_intersections(found)
+}
+
+fn to_intersections(found: Vec<(Segment, Segment, Point)>) -> Vec<Intersection> {
+    let mut intersections: Vec<Intersection> = found
+        .into_iter()
+        .filter(|&(_, _, p)| p != Point::origin())
+        .map(|(sa, sb, p)| Intersection {
+            point: p,
+            steps: (sa.steps_to(p), sb.steps_to(p)),
+        })
+        .collect();
+    intersections.sort_by_key(|i| (i.point, i.steps));
+    intersections.dedup();
+    intersections
+}
+
+/// How to rank crossings, smallest first, of flat wires or, with `Point3`,
+/// wires in space.
+pub enum Metric<'a, P = Point> {
+    /// Manhattan distance to the origin
+    Manhattan,
+    /// Steps all wires meeting there take to get there, combined
+    TotalSteps,
+    /// Steps the longest of those wires takes to get there
+    MaxSteps,
+    /// Chebyshev distance to the origin
+    Chebyshev,
+    /// Anything else, given the crossing and the steps of the wires meeting
+    /// there. Every cell where two wires overlap has to be measured, so long
+    /// overlaps are slow.
+    Custom(&'a dyn Fn(P, &[u64]) -> u64),
+}
+
+impl<P: Coord> Metric<'_, P> {
+    pub fn measure(&self, p: P, steps: &[u64]) -> u64 {
+        match self {
+            Metric::Manhattan => p.manhattan(),
+            Metric::TotalSteps => steps.iter().sum(),
+            Metric::MaxSteps => steps.iter().cloned().max().unwrap_or(0),
+            Metric::Chebyshev => p.chebyshev(),
+            Metric::Custom(f) => f(p, steps),
+        }
+    }
+}
+
+impl Metric<'_> {
+    /// The crossing ranked first, and its distance. Ties go to the crossing
+    /// with the smallest coordinates.
+    ///
+    /// Custom metrics need every crossing, from `intersect_all`; the others
+    /// only the candidates from `intersect`.
+    pub fn best(&self, intersections: &[Intersection]) -> Option<(Point, u64)> {
+        intersections
+            .iter()
+            .map(|i| (i.point, self.measure(i.point, &[i.steps.0, i.steps.1])))
+            .min_by_key(|&(p, d)| (d, p))
+    }
+}
+
+impl<P> FromStr for Metric<'_, P> {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "manhattan" => Ok(Metric::Manhattan),
+            "total-steps" => Ok(Metric::TotalSteps),
+            "max-steps" => Ok(Metric::MaxSteps),
+            "chebyshev" => Ok(Metric::Chebyshev),
+            _ => Err(format!("Unknown metric: '{}'", s)),
+        }
+    }
+}
+
+impl<P> fmt::Debug for Metric<'_, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Metric::Manhattan => write!(f, "Manhattan"),
+            Metric::TotalSteps => write!(f, "TotalSteps"),
+            Metric::MaxSteps => write!(f, "MaxSteps"),
+            Metric::Chebyshev => write!(f, "Chebyshev"),
+            Metric::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+/// The crossing of `a` and `b` ranked first by `metric`, and its distance.
+pub fn best(a: &VectorList, b: &VectorList, metric: &Metric) -> Option<(Point, u64)> {
+    let (a, b) = (segments(a), segments(b));
+    let intersections = match metric {
+        Metric::Custom(_) => intersect_all(&a, &b),
+        _ => intersect(&a, &b),
+    };
+    metric.best(&intersections)
+}
+
+/// Parses two wires and returns their crossing ranked first by `metric`,
+/// and its distance.
+pub fn solve_with(
+    first: &str,
+    second: &str,
+    metric: &Metric,
+) -> Result<Option<(Point, u64)>, ParseError> {
+    let a = notation::parse_wire(first.trim_end())?;
+    let b = notation::parse_wire(second.trim_end())?;
+    Ok(best(&a, &b, metric))
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Minima {
+    /// The crossing closest to the origin, and its Manhattan distance
+    pub closest: Option<(Point, u64)>,
+    /// The crossing reached in the fewest combined steps, and those steps
+    pub shortest: Option<(Point, u64)>,
+}
+
+pub fn minima(a: &VectorList, b: &VectorList) -> Minima {
+    let intersections = intersect(&segments(a), &segments(b));
+    Minima {
+        closest: Metric::Manhattan.best(&intersections),
+        shortest: Metric::TotalSteps.best(&intersections),
+    }
+}
+
+/// The smallest box holding all the segments and the origin
+pub fn extents(segments: &[Segment]) -> BoundingBox {
+    let ends = segments.iter().map(|s| s.end);
+    BoundingBox::of(std::iter::once(Point::origin()).chain(ends)).expect("The origin is a point")
+}
+
+#[cfg(test)]
+use crate::testing::{pallet, wires, REFERENCE};
+
+#[cfg(test)]
+fn minima_of(a: &str, b: &str) -> Minima {
+    let wires = wires(&[a, b]);
+    minima(&wires[0], &wires[1])
+}
+
+#[test]
+fn reference_minima() {
+    let m = minima_of(REFERENCE[0], REFERENCE[1]);
+    assert_eq!(m.closest, Some((Point::new(3, -3), 6)));
+    assert_eq!(m.shortest, Some((Point::new(6, -5), 30)));
+    let m = minima_of(
+        "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+        "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+    );
+    assert_eq!(m.closest.map(|(_, d)| d), Some(135));
+    assert_eq!(m.shortest.map(|(_, d)| d), Some(410));
+}
+
+#[test]
+fn long_segments() {
+    let m = minima_of("R4000000000,U5", "U3,R4000000000,D6");
+    assert_eq!(m.closest, Some((Point::new(4000000000, 0), 4000000000)));
+    assert_eq!(m.shortest.map(|(_, d)| d), Some(8000000006));
+}
+
+#[test]
+fn overlapping_segments() {
+    let m = minima_of("U2,L5,D4", "D2,L5,U1,L1");
+    assert_eq!(m.closest, Some((Point::new(-5, 1), 6)));
+    let m = minima_of("R10", "U1,L5,D1,R20");
+    assert_eq!(m.closest, Some((Point::new(1, 0), 1)));
+    assert_eq!(m.shortest, Some((Point::new(1, 0), 14)));
+}
+
+#[test]
+fn many_overlaps_on_a_line() {
+    // Both wires keep leaving the line y = 0 and coming back to it
+    let a = "R3,U1,R2,D1,R4,D2,L6,U2,L2";
+    let b = "L1,U4,R4,D4,R7,D1,L3,U1,L5";
+    let pallet = pallet(&[a, b]);
+    let m = minima_of(a, b);
+    assert_eq!(m.closest, pallet.closest_crossing());
+    assert_eq!(m.shortest.map(|(_, d)| d), pallet.min_crosspoint_steps());
+}
+
+#[test]
+fn matches_pallet() {
+    let a = "R75,D30,R83,U83,L12,D49,R71,U7,L72,D40,L200,U10";
+    let b = "U62,R66,U55,R34,D71,R55,D58,R83,L300,U20";
+    let pallet = pallet(&[a, b]);
+    let m = minima_of(a, b);
+    assert_eq!(m.closest, pallet.closest_crossing());
+    assert_eq!(m.shortest.map(|(_, d)| d), pallet.min_crosspoint_steps());
+}
+
+#[test]
+fn metrics() {
+    let wires = wires(&REFERENCE);
+    let best = |m| best(&wires[0], &wires[1], &m);
+    assert_eq!(best(Metric::Manhattan), Some((Point::new(3, -3), 6)));
+    assert_eq!(best(Metric::TotalSteps), Some((Point::new(6, -5), 30)));
+    assert_eq!(best(Metric::MaxSteps), Some((Point::new(6, -5), 15)));
+    assert_eq!(best(Metric::Chebyshev), Some((Point::new(3, -3), 3)));
+    let furthest = |p: Point, _: &[u64]| u64::MAX - p.manhattan();
+    assert_eq!(
+        best(Metric::Custom(&furthest)),
+        Some((Point::new(6, -5), u64::MAX - 11))
+    );
+    assert_eq!(best("max-steps".parse().unwrap()), best(Metric::MaxSteps));
+    assert!("euclid".parse::<Metric>().is_err());
+}
+
+#[test]
+fn overlap_pieces() {
+    let pieces = |a, b| {
+        let wires = wires(&[a, b]);
+        overlaps(&segments(&wires[0]), &segments(&wires[1]))
+    };
+    let piece = |(x1, x2), steps| Segment {
+        start: Point::new(x1, 0),
+        end: Point::new(x2, 0),
+        steps,
+    };
+    assert_eq!(
+        pieces("R10", "U1,R2,D1,R8"),
+        [(piece((2, 10), 2), piece((2, 10), 4))]
+    );
+    // Coming back the other way, the second wire's piece starts at the far end
+    assert_eq!(
+        pieces("R10", "R12,U1,L2,D1,L8"),
+        [
+            (piece((0, 10), 0), piece((0, 10), 0)),
+            (piece((2, 10), 2), piece((10, 2), 16))
+        ]
+    );
+}
+
+#[test]
+fn custom_metric_on_overlap() {
+    // Best in the middle of the overlap, away from any built-in candidate
+    let wires = wires(&["R10", "U1,R2,D1,R8"]);
+    let near = |p: Point, _: &[u64]| p.manhattan_to(Point::new(6, 0));
+    assert_eq!(
+        best(&wires[0], &wires[1], &Metric::Custom(&near)),
+        Some((Point::new(6, 0), 0))
+    );
+}
+
+#[test]
+fn max_steps_on_overlap() {
+    use crate::grid::parse_vector_list;
+    // The wires run towards each other along y = -1, the first one having
+    // taken a ten step detour, and meet at the same step count halfway
+    let a = parse_vector_list("D5,U6,R10");
+    let b = parse_vector_list("R10,U1,L10");
+    assert_eq!(
+        best(&a, &b, &Metric::MaxSteps),
+        Some((Point::new(5, -1), 16))
+    );
+}
@@ -0,0 +1,150 @@
+//! Wires that are not flat or not orthogonal: moves may go forward and back
+//! in a third dimension, and diagonally within a plane, each diagonal step
+//! counting as one.
+//!
+//! Wires are laid out in a `wire::Pallet` of `Point3`s and ranked by the
+//! same `segment::Metric`s as flat ones. Wires meet where they share a cell,
+//! so two diagonals passing between the same four cells in an X do not
+//! cross.
+
+use crate::grid::{Coord, Point};
+use crate::notation::{self, Move, Notation, ParseError};
+use crate::wire::Pallet;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point3 {
+    pub fn new(x: i64, y: i64, z: i64) -> Point3 {
+        Point3 { x, y, z }
+    }
+
+    pub fn origin() -> Point3 {
+        Point3::default()
+    }
+
+    /// Manhattan distance to the origin
+    pub fn manhattan(&self) -> u64 {
+        self.x.unsigned_abs() + self.y.unsigned_abs() + self.z.unsigned_abs()
+    }
+
+    /// Chebyshev distance to the origin, the fewest steps to get there when
+    /// moving diagonally in all three dimensions
+    pub fn chebyshev(&self) -> u64 {
+        self.x
+            .unsigned_abs()
+            .max(self.y.unsigned_abs())
+            .max(self.z.unsigned_abs())
+    }
+
+    pub fn step(&self, (dx, dy, dz): (i64, i64, i64)) -> Point3 {
+        Point3::new(self.x + dx, self.y + dy, self.z + dz)
+    }
+}
+
+impl Coord for Point3 {
+    type Delta = (i64, i64, i64);
+
+    fn step(&self, delta: (i64, i64, i64)) -> Point3 {
+        Point3::step(self, delta)
+    }
+
+    fn manhattan(&self) -> u64 {
+        Point3::manhattan(self)
+    }
+
+    fn chebyshev(&self) -> u64 {
+        Point3::chebyshev(self)
+    }
+}
+
+impl From<Point> for Point3 {
+    fn from(p: Point) -> Point3 {
+        Point3::new(p.x, p.y, 0)
+    }
+}
+
+pub type Wire = Vec<Move>;
+
+/// Parses a wire allowing every notation, diagonals and depth included.
+pub fn parse_wire(line: &str) -> Result<Wire, ParseError> {
+    let notation = Notation {
+        compass: true,
+        diagonals: true,
+        depth: true,
+    };
+    notation::parse_moves(line, notation)
+}
+
+/// A layout of wires in space
+pub type Space = Pallet<Point3>;
+
+impl Pallet<Point3> {
+    pub fn draw_wire(&mut self, wire: &[Move]) {
+        let moves = wire.iter().map(|m| (m.direction.delta(), m.len));
+        self.draw_moves(moves, |_| ());
+    }
+}
+
+#[cfg(test)]
+use crate::segment::Metric;
+
+#[cfg(test)]
+fn space(wires: &[&str]) -> Space {
+    let mut space = Space::new();
+    for w in wires {
+        space.draw_wire(&parse_wire(w).unwrap());
+    }
+    space
+}
+
+#[test]
+fn flat_wires_match_pallet() {
+    let wires = [
+        "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+        "U62,R66,U55,R34,D71,R55,D58,R83",
+    ];
+    let pallet = crate::testing::pallet(&wires);
+    let space = space(&wires);
+    let flat = |best: Option<(Point, u64)>| best.map(|(p, d)| (Point3::from(p), d));
+    assert_eq!(
+        space.best(&Metric::Manhattan),
+        flat(pallet.closest_crossing())
+    );
+    assert_eq!(
+        space.best(&Metric::TotalSteps),
+        flat(pallet.shortest_crossing())
+    );
+}
+
+#[test]
+fn depth() {
+    // The second wire passes over the first one, then comes down on it
+    let s = space(&["R5,F2", "F1,R3,B1,R1"]);
+    assert_eq!(s.crossings(), [Point3::new(3, 0, 0), Point3::new(4, 0, 0)]);
+    assert_eq!(s.steps_to(Point3::new(4, 0, 0)), [4, 6]);
+    let s = space(&["R5,F2", "F2,R5"]);
+    assert_eq!(s.best(&Metric::Manhattan), Some((Point3::new(5, 0, 2), 7)));
+}
+
+#[test]
+fn diagonals() {
+    let s = space(&["UR4", "R2,U4"]);
+    assert_eq!(s.crossings(), [Point3::new(2, -2, 0)]);
+    assert_eq!(s.steps_to(Point3::new(2, -2, 0)), [2, 4]);
+    assert_eq!(s.best(&Metric::Chebyshev), Some((Point3::new(2, -2, 0), 2)));
+    assert_eq!(s.best(&Metric::MaxSteps), Some((Point3::new(2, -2, 0), 4)));
+    // Crossing in an X between cells is not meeting
+    let s = space(&["UR2", "R1,UL2"]);
+    assert!(s.crossings().is_empty());
+    let far = |p: Point3, steps: &[u64]| 100 - p.manhattan() - steps.len() as u64;
+    let s = space(&["R3,U3", "U3,R3", "UR3"]);
+    assert_eq!(
+        s.best(&Metric::Custom(&far)),
+        Some((Point3::new(3, -3, 0), 91))
+    );
+}
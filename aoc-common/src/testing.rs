@@ -0,0 +1,20 @@
+//! Layouts shared by the tests, and ways to set them up.
+
+use crate::grid::{parse_vector_list, VectorList};
+use crate::wire::Pallet;
+
+/// The first example layout of the puzzle
+pub const REFERENCE: [&str; 2] = ["R8,U5,L5,D3", "U7,R6,D4,L4"];
+
+pub fn wires(wires: &[&str]) -> Vec<VectorList> {
+    wires.iter().map(|w| parse_vector_list(w)).collect()
+}
+
+/// A pallet with `wires` drawn on it, in order
+pub fn pallet(wires: &[&str]) -> Pallet {
+    let mut pallet = Pallet::new();
+    for w in wires {
+        pallet.draw(&parse_vector_list(w));
+    }
+    pallet
+}
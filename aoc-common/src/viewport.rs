@@ -0,0 +1,178 @@
+//! Terminal views of any part of a wire layout, however large, at any zoom.
+
+use crate::grid::{BoundingBox, Point};
+use crate::wire::Pallet;
+
+/// How far from the origin the view may be centered, and stretch out from
+/// its center
+const LIMIT: i64 = i64::MAX / 4;
+
+/// A window onto a layout, `width` by `height` characters, each showing
+/// `zoom` by `zoom` cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub center: Point,
+    pub width: usize,
+    pub height: usize,
+    pub zoom: u64,
+}
+
+impl Viewport {
+    /// The smallest zoom showing all of `bounds`, centered on them
+    pub fn fit(bounds: BoundingBox, width: usize, height: usize) -> Viewport {
+        let (width, height) = (width.max(1), height.max(1));
+        let zoom = bounds
+            .width()
+            .div_ceil(width as u64)
+            .max(bounds.height().div_ceil(height as u64));
+        // Split the room left over evenly on both sides
+        let center = |min: i64, cells: u64, chars: usize| {
+            let room = chars as i64 * zoom as i64 - cells as i64;
+            min - room / 2 + (chars / 2) as i64 * zoom as i64
+        };
+        Viewport {
+            center: Point::new(
+                center(bounds.min.x, bounds.width(), width),
+                center(bounds.min.y, bounds.height(), height),
+            ),
+            width,
+            height,
+            zoom,
+        }
+    }
+
+    /// Moves the view by `dx` and `dy` characters
+    pub fn pan(&mut self, dx: i64, dy: i64) {
+        let zoom = self.zoom as i64;
+        let moved = |c: i64, d: i64| {
+            c.saturating_add(d.saturating_mul(zoom))
+                .clamp(-LIMIT, LIMIT)
+        };
+        self.center = Point::new(moved(self.center.x, dx), moved(self.center.y, dy));
+    }
+
+    pub fn zoom_in(&mut self) {
+        self.zoom = (self.zoom / 2).max(1);
+    }
+
+    /// Doubles the zoom, up to where the view would no longer fit in an
+    /// `i64` around its center
+    pub fn zoom_out(&mut self) {
+        let max = LIMIT as u64 / self.width.max(self.height).max(1) as u64;
+        self.zoom = self.zoom.saturating_mul(2).min(max.max(self.zoom));
+    }
+
+    /// The cell in the top left corner
+    fn corner(&self) -> Point {
+        let zoom = self.zoom as i64;
+        Point::new(
+            self.center.x - (self.width / 2) as i64 * zoom,
+            self.center.y - (self.height / 2) as i64 * zoom,
+        )
+    }
+
+    /// The character showing `p`, if it is in view
+    fn locate(&self, p: Point) -> Option<(usize, usize)> {
+        let corner = self.corner();
+        let zoom = self.zoom as i64;
+        let col = (p.x - corner.x).div_euclid(zoom);
+        let row = (p.y - corner.y).div_euclid(zoom);
+        if col < 0 || row < 0 || col >= self.width as i64 || row >= self.height as i64 {
+            None
+        } else {
+            Some((col as usize, row as usize))
+        }
+    }
+
+    /// Draws the part of `pallet` in view like `Pallet::to_ascii`, each
+    /// character showing the most interesting of the cells it covers
+    pub fn render(&self, pallet: &Pallet, highlight: Option<Point>) -> String {
+        let mut chars = vec![' '; self.width * self.height];
+        let mut mark = |p: Point, c: char| {
+            if let Some((col, row)) = self.locate(p) {
+                let shown = &mut chars[row * self.width + col];
+                if rank(c) > rank(*shown) {
+                    *shown = c;
+                }
+            }
+        };
+        for wire in 0..pallet.wire_count() {
+            for p in pallet.cells(wire) {
+                mark(p, if pallet.visits(p) > 1 { '!' } else { 'x' });
+            }
+        }
+        mark(Point::origin(), 'o');
+        if let Some(p) = highlight {
+            mark(p, '*');
+        }
+
+        let border = format!("|{}|\n", "-".repeat(self.width));
+        let mut ascii = border.clone();
+        for row in chars.chunks(self.width.max(1)) {
+            ascii.push('|');
+            ascii.extend(row);
+            ascii.push_str("|\n");
+        }
+        ascii.push_str(&border);
+        ascii
+    }
+}
+
+/// Which character wins when several cells share one
+fn rank(c: char) -> u8 {
+    match c {
+        'x' => 1,
+        '!' => 2,
+        'o' => 3,
+        '*' => 4,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+use crate::testing::{pallet, REFERENCE};
+
+#[test]
+fn full_zoom_matches_ascii() {
+    let pallet = pallet(&REFERENCE);
+    let view = Viewport::fit(pallet.extents(), 9, 8);
+    assert_eq!(view.zoom, 1);
+    let highlight = Some(Point::new(6, -5));
+    assert_eq!(view.render(&pallet, highlight), pallet.to_ascii(highlight));
+}
+
+#[test]
+fn downsampled_view() {
+    let pallet = pallet(&REFERENCE);
+    let mut view = Viewport::fit(pallet.extents(), 3, 2);
+    assert_eq!(view.zoom, 4);
+    assert_eq!(view.render(&pallet, None), "|---|\n|x!x|\n|o!x|\n|---|\n");
+    view.zoom_in();
+    view.pan(0, -1);
+    assert_eq!(view.zoom, 2);
+    assert_eq!(view.corner(), Point::new(1, -7));
+    assert_eq!(view.render(&pallet, None), "|---|\n|xxx|\n| x!|\n|---|\n");
+}
+
+#[test]
+fn huge_layout() {
+    let pallet = pallet(&["R100000,U3"]);
+    let view = Viewport::fit(pallet.extents(), 10, 1);
+    assert_eq!(view.zoom, 10001);
+    assert_eq!(
+        view.render(&pallet, None),
+        "|----------|\n|oxxxxxxxxx|\n|----------|\n"
+    );
+}
+
+#[test]
+fn zoom_and_pan_stay_in_range() {
+    let pallet = pallet(&REFERENCE);
+    let mut view = Viewport::fit(pallet.extents(), 80, 24);
+    for _ in 0..100 {
+        view.zoom_out();
+        view.pan(-20, 6);
+    }
+    assert_eq!(view.zoom, LIMIT as u64 / 80);
+    assert_eq!(view.render(&pallet, None).lines().count(), 26);
+}
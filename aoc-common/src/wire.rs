@@ -0,0 +1,314 @@
+//! Sparse wire layouts: only the cells a wire actually visits are stored, so
+//! the extents of a layout do not matter, only the length of its wires.
+//!
+//! Layouts are flat unless made of `space::Point3`, for wires in space.
+
+use crate::grid::{BoundingBox, Coord, Point, VectorList};
+use crate::segment::{extents, segments, Metric, Segment};
+use crate::viewport::Viewport;
+use std::collections::HashMap;
+
+/// A pair of wires and the crossing between them closest to the origin
+pub type PairCrossing<P = Point> = ((usize, usize), Option<(P, u64)>);
+
+#[derive(Default)]
+pub struct Pallet<P = Point> {
+    /// Per wire, the number of steps taken to first reach each visited cell
+    wires: Vec<HashMap<P, u64>>,
+}
+
+impl<P: Coord> Pallet<P> {
+    pub fn new() -> Pallet<P> {
+        Pallet { wires: Vec::new() }
+    }
+
+    /// Draws a wire making `len` steps of `delta` for every move, calling
+    /// `on_step` after every step it takes
+    pub fn draw_moves<I, F>(&mut self, moves: I, mut on_step: F)
+    where
+        I: IntoIterator<Item = (P::Delta, usize)>,
+        F: FnMut(&Pallet<P>),
+    {
+        let wire = self.wires.len();
+        let mut p = P::default();
+        let mut steps = 0;
+        self.wires.push(HashMap::new());
+        self.wires[wire].insert(p, steps);
+        for (delta, len) in moves {
+            for _ in 0..len {
+                p = p.step(delta);
+                steps += 1;
+                self.wires[wire].entry(p).or_insert(steps);
+                on_step(self);
+            }
+        }
+    }
+
+    pub fn wire_count(&self) -> usize {
+        self.wires.len()
+    }
+
+    /// Steps `wire` takes to first reach `p`, if it ever does
+    pub fn steps(&self, wire: usize, p: P) -> Option<u64> {
+        self.wires.get(wire)?.get(&p).cloned()
+    }
+
+    /// Steps each wire passing through `p` takes to reach it, in wire order
+    pub fn steps_to(&self, p: P) -> Vec<u64> {
+        self.wires
+            .iter()
+            .filter_map(|w| w.get(&p))
+            .cloned()
+            .collect()
+    }
+
+    /// Cells visited by `wire`, in no particular order
+    pub fn cells(&self, wire: usize) -> impl Iterator<Item = P> + '_ {
+        self.wires[wire].keys().cloned()
+    }
+
+    /// Number of wires visiting `p`
+    pub fn visits(&self, p: P) -> usize {
+        self.wires.iter().filter(|w| w.contains_key(&p)).count()
+    }
+
+    /// Cells, other than the origin, visited by at least two wires
+    pub fn crossings(&self) -> Vec<P> {
+        self.crossed_by(2).into_iter().map(|(p, _)| p).collect()
+    }
+
+    /// Cells, other than the origin, visited by at least `k` wires, sorted,
+    /// together with the wires visiting each of them
+    pub fn crossed_by(&self, k: usize) -> Vec<(P, Vec<usize>)> {
+        let mut visits: HashMap<P, Vec<usize>> = HashMap::new();
+        for (i, cells) in self.wires.iter().enumerate() {
+            for &p in cells.keys() {
+                visits.entry(p).or_default().push(i);
+            }
+        }
+        let mut crossed: Vec<(P, Vec<usize>)> = visits
+            .into_iter()
+            .filter(|(p, wires)| *p != P::default() && wires.len() >= k)
+            .collect();
+        crossed.sort();
+        crossed
+    }
+
+    /// For every pair of wires, the crossing between just those two that is
+    /// closest to the origin
+    pub fn pairwise_closest(&self) -> Vec<PairCrossing<P>> {
+        let mut pairs = Vec::new();
+        for i in 0..self.wires.len() {
+            for j in i + 1..self.wires.len() {
+                let (small, large) = if self.wires[i].len() < self.wires[j].len() {
+                    (&self.wires[i], &self.wires[j])
+                } else {
+                    (&self.wires[j], &self.wires[i])
+                };
+                let closest = small
+                    .keys()
+                    .filter(|p| **p != P::default() && large.contains_key(p))
+                    .map(|&p| (p, p.manhattan()))
+                    .min_by_key(|&(p, d)| (d, p));
+                pairs.push(((i, j), closest));
+            }
+        }
+        pairs
+    }
+
+    /// The cell visited by every wire that takes the fewest steps in total to
+    /// reach, and those steps
+    pub fn shortest_common_crossing(&self) -> Option<(P, u64)> {
+        self.crossed_by(self.wires.len().max(2))
+            .into_iter()
+            .map(|(p, _)| (p, self.total_steps(p)))
+            .min_by_key(|&(_, d)| d)
+    }
+
+    /// Total steps all wires passing through `p` take to reach it
+    pub fn total_steps(&self, p: P) -> u64 {
+        self.wires.iter().filter_map(|w| w.get(&p)).sum()
+    }
+
+    /// The crossing ranked first by `metric`, and its distance. Ties go to
+    /// the crossing with the smallest coordinates.
+    pub fn best(&self, metric: &Metric<P>) -> Option<(P, u64)> {
+        self.crossings()
+            .into_iter()
+            .map(|p| (p, metric.measure(p, &self.steps_to(p))))
+            .min_by_key(|&(p, d)| (d, p))
+    }
+
+    /// The crossing closest to the origin, and its Manhattan distance
+    pub fn closest_crossing(&self) -> Option<(P, u64)> {
+        self.best(&Metric::Manhattan)
+    }
+
+    /// The crossing reached in the fewest combined steps, and those steps
+    pub fn shortest_crossing(&self) -> Option<(P, u64)> {
+        self.best(&Metric::TotalSteps)
+    }
+
+    pub fn min_crosspoint_distance(&self) -> Option<u64> {
+        self.closest_crossing().map(|(_, d)| d)
+    }
+
+    pub fn min_crosspoint_steps(&self) -> Option<u64> {
+        self.shortest_crossing().map(|(_, d)| d)
+    }
+}
+
+impl Pallet {
+    pub fn draw(&mut self, vl: &VectorList) {
+        self.draw_with(vl, |_| ());
+    }
+
+    /// Draws a wire like `draw`, calling `on_step` after every step it takes
+    pub fn draw_with<F: FnMut(&Pallet)>(&mut self, vl: &VectorList, on_step: F) {
+        self.draw_moves(vl.iter().map(|v| (v.delta(), v.len())), on_step);
+    }
+
+    /// Draws `wires` one after another, passing `on_frame` a picture of the
+    /// layout so far at `count` evenly spread steps, the last one showing the
+    /// finished layout. Pictures are `width` by `height` characters, zoomed
+    /// out to fit the finished layout. `highlight` shows once two wires have
+    /// reached it.
+    pub fn frames<F: FnMut(String)>(
+        wires: &[VectorList],
+        highlight: Option<Point>,
+        count: u64,
+        (width, height): (usize, usize),
+        mut on_frame: F,
+    ) {
+        let all: Vec<Segment> = wires.iter().flat_map(segments).collect();
+        let viewport = Viewport::fit(extents(&all), width, height);
+        let total: u64 = wires.iter().flatten().map(|v| v.len() as u64).sum();
+        let mut shots = (1..=count)
+            .map(|f| (u128::from(total) * u128::from(f) / u128::from(count)) as u64)
+            .filter(|&step| step > 0)
+            .peekable();
+        let mut step = 0;
+        let mut pallet = Pallet::new();
+        for vl in wires {
+            pallet.draw_with(vl, |p| {
+                step += 1;
+                if shots.peek() != Some(&step) {
+                    return;
+                }
+                while shots.next_if_eq(&step).is_some() {}
+                let shown = highlight.filter(|&h| p.visits(h) > 1);
+                on_frame(viewport.render(p, shown));
+            });
+        }
+    }
+
+    /// The smallest box holding all visited cells
+    pub fn extents(&self) -> BoundingBox {
+        let cells = self.wires.iter().flat_map(|w| w.keys().cloned());
+        BoundingBox::of(cells).unwrap_or_else(|| BoundingBox::new(Point::origin(), Point::origin()))
+    }
+
+    pub fn print(&self, highlight: Option<Point>) {
+        print!("{}", self.to_ascii(highlight));
+    }
+
+    /// The layout drawn with `o` for the origin, `x` for wires, `!` for
+    /// crossings and `*` for `highlight`, in a `|---|` box
+    pub fn to_ascii(&self, highlight: Option<Point>) -> String {
+        self.ascii_within(self.extents(), highlight)
+    }
+
+    fn ascii_within(&self, extents: BoundingBox, highlight: Option<Point>) -> String {
+        let (min, max) = (extents.min, extents.max);
+        let border = format!("|{}|\n", "-".repeat(extents.width() as usize));
+        let mut ascii = border.clone();
+        for y in min.y..=max.y {
+            ascii.push('|');
+            for x in min.x..=max.x {
+                let p = Point::new(x, y);
+                ascii.push(if p == Point::origin() {
+                    'o'
+                } else if Some(p) == highlight {
+                    '*'
+                } else {
+                    match self.visits(p) {
+                        0 => ' ',
+                        1 => 'x',
+                        _ => '!',
+                    }
+                });
+            }
+            ascii.push_str("|\n");
+        }
+        ascii.push_str(&border);
+        ascii
+    }
+}
+
+#[cfg(test)]
+use crate::testing::{pallet, REFERENCE};
+
+#[test]
+fn crossings_works() {
+    let p = pallet(&REFERENCE);
+    assert_eq!(p.crossings(), [Point::new(3, -3), Point::new(6, -5)]);
+    assert_eq!(p.closest_crossing(), Some((Point::new(3, -3), 6)));
+    assert_eq!(p.shortest_crossing(), Some((Point::new(6, -5), 30)));
+}
+
+#[test]
+fn far_extents() {
+    let p = pallet(&["L100000,U3,R3", "U2,L99999,U5"]);
+    assert_eq!(
+        p.extents(),
+        BoundingBox::new(Point::new(-100000, -7), Point::origin())
+    );
+    assert_eq!(p.min_crosspoint_distance(), Some(100002));
+    assert_eq!(p.min_crosspoint_steps(), Some(100004 + 100002));
+}
+
+#[test]
+fn endpoint_counts() {
+    // The wires only meet at the very end of the first one
+    let p = pallet(&["R5", "U1,R5,D1"]);
+    assert_eq!(p.closest_crossing(), Some((Point::new(5, 0), 5)));
+}
+
+#[test]
+fn many_wires() {
+    let p = pallet(&[REFERENCE[0], REFERENCE[1], "U3,R6"]);
+    assert_eq!(p.wire_count(), 3);
+    assert_eq!(p.crossed_by(3), [(Point::new(3, -3), vec![0, 1, 2])]);
+    // Wires 1 and 2 run together from the origin up to (0, -3)
+    assert_eq!(p.crossings().len(), 9);
+    assert_eq!(
+        p.pairwise_closest(),
+        [
+            ((0, 1), Some((Point::new(3, -3), 6))),
+            ((0, 2), Some((Point::new(3, -3), 6))),
+            ((1, 2), Some((Point::new(0, -1), 1)))
+        ]
+    );
+    assert_eq!(p.shortest_common_crossing(), Some((Point::new(3, -3), 46)));
+}
+
+#[test]
+fn frames_show_progress() {
+    use crate::grid::parse_vector_list;
+    let wires = [parse_vector_list("R2"), parse_vector_list("U1,R1,D1")];
+    let frames = |count| {
+        let mut frames = Vec::new();
+        Pallet::frames(&wires, Some(Point::new(1, 0)), count, (3, 2), |f| {
+            frames.push(f)
+        });
+        frames
+    };
+    // Fewer steps than frames shows every step once
+    let every = frames(100);
+    assert_eq!(every.len(), 5);
+    assert_eq!(every[0], "|---|\n|   |\n|ox |\n|---|\n");
+    assert_eq!(every[3], "|---|\n|xx |\n|oxx|\n|---|\n");
+    assert_eq!(every[4], "|---|\n|xx |\n|o*x|\n|---|\n");
+    let sampled = frames(2);
+    assert_eq!(sampled, [every[1].clone(), every[4].clone()]);
+}
@@ -0,0 +1,163 @@
+//! Benchmarks for every day's solution.
+//!
+//! Run `./bench.sh` from the repository root to store the results as a
+//! criterion baseline named after the current commit, and compare against
+//! an earlier one with `cargo bench --bench days -- --baseline <commit>`.
+
+use aoc_common::fuel::{Mass, Strategy};
+use aoc_common::fuel_table::{self, FuelTable};
+use aoc_common::password::{self, PasswordIterator};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+const DAY1: &str = include_str!("../../day1-first/src/input");
+const DAY2: &str = include_str!("../../day2-first/src/input");
+const DAY3: &str = include_str!("../../day3-first/src/input");
+const DAY3_REFERENCE: (&str, &str) = (
+    "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+    "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+);
+
+fn day1(c: &mut Criterion) {
+    let masses: Vec<Mass> = DAY1.lines().map(|l| l.trim().parse().unwrap()).collect();
+    c.bench_function("day1 fuel_required", |b| {
+        b.iter(|| {
+            black_box(&masses)
+                .iter()
+                .map(|&m| Strategy::Simple.fuel_required(m))
+                .sum::<Mass>()
+        })
+    });
+    c.bench_function("day1 fuel_required recursive", |b| {
+        b.iter(|| {
+            black_box(&masses)
+                .iter()
+                .map(|&m| Strategy::Recursive.fuel_required(m))
+                .sum::<Mass>()
+        })
+    });
+}
+
+/// Recursive fuel over a large generated manifest, stage by stage versus
+/// the closed form.
+fn fuel_throughput(c: &mut Criterion) {
+    const MODULES: u64 = 1_000_000;
+    // Deterministic pseudo-random masses, up to ~10^12
+    let masses: Vec<u64> = (0..MODULES)
+        .map(|i| {
+            i.wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407)
+                >> 24
+        })
+        .collect();
+    let wide: Vec<u128> = masses.iter().map(|&m| (m as u128) << 40).collect();
+    let table = FuelTable::new();
+
+    let mut group = c.benchmark_group("fuel throughput");
+    group.throughput(Throughput::Elements(MODULES));
+    group.sample_size(10);
+    group.bench_function("recursive u64", |b| {
+        b.iter(|| {
+            black_box(&masses)
+                .iter()
+                .map(|&m| fuel_table::recursive_fuel(m))
+                .sum::<u64>()
+        })
+    });
+    group.bench_function("closed form u64", |b| {
+        b.iter(|| {
+            black_box(&masses)
+                .iter()
+                .map(|&m| table.fuel_required(m))
+                .sum::<u64>()
+        })
+    });
+    group.bench_function("plan u64", |b| {
+        b.iter(|| {
+            black_box(&masses)
+                .iter()
+                .map(|&m| Strategy::Recursive.fuel_required(Mass(m)))
+                .sum::<Mass>()
+        })
+    });
+    group.bench_function("recursive u128", |b| {
+        b.iter(|| {
+            black_box(&wide)
+                .iter()
+                .map(|&m| fuel_table::recursive_fuel(m))
+                .sum::<u128>()
+        })
+    });
+    group.bench_function("closed form u128", |b| {
+        b.iter(|| {
+            black_box(&wide)
+                .iter()
+                .map(|&m| table.fuel_required(m))
+                .sum::<u128>()
+        })
+    });
+    group.finish();
+}
+
+fn day2(c: &mut Criterion) {
+    let golden = aoc_common::intcode::Program::parse(DAY2);
+    c.bench_function("day2 Program::execute", |b| {
+        b.iter(|| black_box(&golden).clone().execute()[0])
+    });
+    c.bench_function("day2 find_noun_verb", |b| {
+        b.iter(|| day2_second::find_noun_verb(black_box(&golden), 19690720))
+    });
+}
+
+fn day3(c: &mut Criterion) {
+    let (s1, s2) = DAY3_REFERENCE;
+    c.bench_function("day3 solve reference", |b| {
+        b.iter(|| day3_first::solve(black_box(s1), black_box(s2)))
+    });
+    c.bench_function("day3 solve reference steps", |b| {
+        b.iter(|| day3_second::solve(black_box(s1), black_box(s2)))
+    });
+
+    let mut lines = DAY3.lines();
+    let (s1, s2) = (lines.next().unwrap(), lines.next().unwrap());
+    let mut group = c.benchmark_group("day3 input");
+    group.sample_size(10);
+    group.bench_function("solve", |b| {
+        b.iter(|| day3_first::solve(black_box(s1), black_box(s2)))
+    });
+    group.bench_function("solve steps", |b| {
+        b.iter(|| day3_second::solve(black_box(s1), black_box(s2)))
+    });
+    group.finish();
+}
+
+fn day4(c: &mut Criterion) {
+    c.bench_function("day4 PasswordIterator count", |b| {
+        b.iter(|| {
+            PasswordIterator {
+                current: black_box(234208),
+                stop: 765869,
+                rule: day4_first::rule(),
+            }
+            .count()
+        })
+    });
+    c.bench_function("day4 PasswordIterator count exact pair", |b| {
+        b.iter(|| {
+            PasswordIterator {
+                current: black_box(234208),
+                stop: 765869,
+                rule: day4_second::rule(),
+            }
+            .count()
+        })
+    });
+    c.bench_function("day4 sorted count exact pair", |b| {
+        b.iter(|| password::count_sorted(black_box(234208), 765869, &day4_second::rule()))
+    });
+    c.bench_function("day4 counted exact pair", |b| {
+        b.iter(|| password::count(black_box(234208), 765869, &day4_second::runs()))
+    });
+}
+
+criterion_group!(benches, day1, fuel_throughput, day2, day3, day4);
+criterion_main!(benches);
@@ -0,0 +1,36 @@
+use crate::solution::Solution;
+use aoc_common::fuel::{Module, Plan, Strategy};
+use aoc_common::manifest::{self, Format, Policy};
+
+pub struct Day1;
+
+fn total(strategy: Strategy, modules: &[Module]) -> Result<String, String> {
+    Plan::new(strategy, modules.iter().cloned())
+        .map(|plan| plan.total.to_string())
+        .map_err(|e| e.to_string())
+}
+
+impl Solution for Day1 {
+    type Input = Vec<Module>;
+
+    fn parse(&self, text: &str) -> Result<Vec<Module>, String> {
+        manifest::read(text.as_bytes(), Format::Auto, Policy::Fail)
+            .map(|manifest| manifest.modules)
+            .map_err(|e| e.to_string())
+    }
+
+    fn part1(&self, modules: &Vec<Module>) -> Result<String, String> {
+        total(Strategy::Simple, modules)
+    }
+
+    fn part2(&self, modules: &Vec<Module>) -> Result<String, String> {
+        total(Strategy::Recursive, modules)
+    }
+}
+
+#[test]
+fn invalid_mass() {
+    assert!(Day1.parse("12\nabc\n").is_err());
+    let modules = Day1.parse("12\n").unwrap();
+    assert_eq!(Day1.part1(&modules), Ok("2".to_string()));
+}
@@ -0,0 +1,49 @@
+use crate::solution::Solution;
+use aoc_common::intcode::Program;
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Program;
+
+    fn parse(&self, text: &str) -> Result<Program, String> {
+        let data = text
+            .split(',')
+            .map(|x| {
+                x.trim()
+                    .parse()
+                    .map_err(|_| format!("Number parse failed: '{}'", x.trim()))
+            })
+            .collect::<Result<Vec<usize>, String>>()?;
+        Ok(Program::from(&data))
+    }
+
+    fn part1(&self, golden: &Program) -> Result<String, String> {
+        if golden.data.len() < 3 {
+            return Err("Program too short for a noun and verb".to_string());
+        }
+        // Restore the "1202 program alarm" state before running
+        let mut program = golden.clone();
+        program.data[1] = 12;
+        program.data[2] = 2;
+        Ok(program.execute()[0].to_string())
+    }
+
+    fn part2(&self, golden: &Program) -> Result<String, String> {
+        if golden.data.len() < 3 {
+            return Err("Program too short for a noun and verb".to_string());
+        }
+        Ok(match day2_second::find_noun_verb(golden, 19690720) {
+            Some(result) => result.to_string(),
+            None => "none".to_string(),
+        })
+    }
+}
+
+#[test]
+fn invalid_program() {
+    assert!(Day2.parse("1,x,3").is_err());
+    let short = Day2.parse("1,2\n").unwrap();
+    assert!(Day2.part1(&short).is_err());
+    assert!(Day2.part2(&short).is_err());
+}
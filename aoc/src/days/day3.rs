@@ -0,0 +1,50 @@
+use crate::solution::Solution;
+use aoc_common::notation::{self, ParseError};
+
+pub struct Day3;
+
+/// The distance to report, or "none" if the wires never cross
+fn answer(found: Result<Option<u64>, ParseError>) -> Result<String, String> {
+    match found {
+        Ok(Some(d)) => Ok(d.to_string()),
+        Ok(None) => Ok("none".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+impl Solution for Day3 {
+    type Input = (String, String);
+
+    fn parse(&self, text: &str) -> Result<(String, String), String> {
+        let mut lines = text.lines();
+        let first = lines.next().ok_or("Missing first wire")?;
+        let second = lines.next().ok_or("Missing second wire")?;
+        // Check both wires up front, to point at the problem in its line
+        for (n, line) in [first, second].iter().enumerate() {
+            let line = line.trim_end();
+            notation::parse_wire(line)
+                .map_err(|e| format!("wire {}:\n{}", n + 1, e.render(line)))?;
+        }
+        Ok((first.to_string(), second.to_string()))
+    }
+
+    fn part1(&self, (first, second): &(String, String)) -> Result<String, String> {
+        answer(day3_first::solve(first, second))
+    }
+
+    fn part2(&self, (first, second): &(String, String)) -> Result<String, String> {
+        answer(day3_second::solve(first, second))
+    }
+}
+
+#[test]
+fn reference_test() {
+    let input = Day3.parse("R8,U5,L5,D3\nU7,R6,D4,L4\n").unwrap();
+    assert_eq!(Day3.part1(&input), Ok("6".to_string()));
+    assert_eq!(Day3.part2(&input), Ok("30".to_string()));
+    assert!(Day3.parse("R8,U5,L5,D3\n").is_err());
+    assert_eq!(
+        Day3.parse("R8,X5\nU1\n"),
+        Err("wire 1:\nR8,X5\n   ^ move 1 at byte 3: unknown direction 'X'".to_string())
+    );
+}
@@ -0,0 +1,35 @@
+use crate::solution::Solution;
+use aoc_common::password;
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = (u128, u128);
+
+    fn parse(&self, text: &str) -> Result<(u128, u128), String> {
+        let (start, stop) = text
+            .trim()
+            .split_once('-')
+            .ok_or_else(|| format!("Range has no '-': '{}'", text.trim()))?;
+        let bound = |x: &str| {
+            x.parse()
+                .map_err(|_| format!("Range parse failed: '{}'", x))
+        };
+        Ok((bound(start)?, bound(stop)?))
+    }
+
+    fn part1(&self, &(start, stop): &(u128, u128)) -> Result<String, String> {
+        Ok(password::count(start, stop, &day4_first::runs()).to_string())
+    }
+
+    fn part2(&self, &(start, stop): &(u128, u128)) -> Result<String, String> {
+        Ok(password::count(start, stop, &day4_second::runs()).to_string())
+    }
+}
+
+#[test]
+fn parse_range() {
+    assert_eq!(Day4.parse("234208-765869\n"), Ok((234208, 765869)));
+    assert!(Day4.parse("").is_err());
+    assert!(Day4.parse("1-x").is_err());
+}
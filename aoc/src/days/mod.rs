@@ -0,0 +1,49 @@
+use crate::solution::Runner;
+use std::path::PathBuf;
+
+mod day1;
+mod day2;
+mod day3;
+mod day4;
+
+pub struct Day {
+    pub number: u8,
+    /// Puzzle input, relative to the repository root
+    pub input: &'static str,
+    pub solution: &'static dyn Runner,
+}
+
+impl Day {
+    pub fn default_input(&self) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("..")
+            .join(self.input)
+    }
+}
+
+pub static DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        input: "day1-first/src/input",
+        solution: &day1::Day1,
+    },
+    Day {
+        number: 2,
+        input: "day2-first/src/input",
+        solution: &day2::Day2,
+    },
+    Day {
+        number: 3,
+        input: "day3-first/src/input",
+        solution: &day3::Day3,
+    },
+    Day {
+        number: 4,
+        input: "day4-first/src/input",
+        solution: &day4::Day4,
+    },
+];
+
+pub fn get(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
@@ -0,0 +1,200 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+mod days;
+mod solution;
+mod verify;
+
+const USAGE: &str = "Usage: aoc run [DAY] [--part PART] [--input PATH]
+       aoc verify [DAY] [--input PATH]";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Run {
+        day: Option<u8>,
+        part: Option<u8>,
+        input: Option<PathBuf>,
+    },
+    Verify {
+        day: Option<u8>,
+        input: Option<PathBuf>,
+    },
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut args = args.iter();
+    let command = match args.next().map(String::as_str) {
+        Some(c @ "run") | Some(c @ "verify") => c,
+        Some(c) => return Err(format!("Unknown command: '{}'", c)),
+        None => return Err("Missing command".to_string()),
+    };
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" if command == "run" => {
+                let p = args.next().ok_or("Missing value for --part")?;
+                part = match p.parse() {
+                    Ok(p @ 1..=2) => Some(p),
+                    _ => return Err(format!("Invalid part: '{}'", p)),
+                };
+            }
+            "--input" | "-i" => {
+                let i = args.next().ok_or("Missing value for --input")?;
+                input = Some(PathBuf::from(i));
+            }
+            d if day.is_none() => {
+                day = Some(d.parse().map_err(|_| format!("Invalid day: '{}'", d))?);
+            }
+            a => return Err(format!("Unexpected argument: '{}'", a)),
+        }
+    }
+    if input.is_some() && day.is_none() {
+        return Err("--input requires a day".to_string());
+    }
+    if command == "verify" {
+        Ok(Command::Verify { day, input })
+    } else {
+        Ok(Command::Run { day, part, input })
+    }
+}
+
+fn run(day: &days::Day, parts: &[u8], input: Option<PathBuf>) -> Result<(), String> {
+    let path = input.unwrap_or_else(|| day.default_input());
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let run = day
+        .solution
+        .run(&text, parts)
+        .map_err(|e| format!("Day {}: {}", day.number, e))?;
+    println!("Day {} (parse {:?})", day.number, run.parse);
+    for answer in run.answers {
+        println!(
+            "  Part {}: {} ({:?})",
+            answer.part, answer.value, answer.elapsed
+        );
+    }
+    Ok(())
+}
+
+fn verify(day: &days::Day, input: Option<PathBuf>) -> Result<usize, String> {
+    let path = input.unwrap_or_else(|| day.default_input());
+    let text = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let answers = verify::Answers::load(&verify::answers_path(&path))?;
+    let mut failed = 0;
+    for check in verify::verify(day, &text, &answers) {
+        println!(
+            "Day {} part {}: {} {} ({:?})",
+            day.number,
+            check.part,
+            check.status,
+            check.value.as_deref().unwrap_or("-"),
+            check.elapsed
+        );
+        match check.status {
+            verify::Status::Pass | verify::Status::Unknown => (),
+            _ => failed += 1,
+        }
+    }
+    Ok(failed)
+}
+
+fn execute(command: Command) -> Result<(), String> {
+    match command {
+        Command::Run { day, part, input } => {
+            let parts = match part {
+                Some(p) => vec![p],
+                None => vec![1, 2],
+            };
+            match day {
+                Some(n) => {
+                    let day = days::get(n).ok_or(format!("Day {} is not solved", n))?;
+                    run(day, &parts, input)
+                }
+                None => days::DAYS.iter().try_for_each(|d| run(d, &parts, None)),
+            }
+        }
+        Command::Verify { day, input } => {
+            let failed = match day {
+                Some(n) => {
+                    let day = days::get(n).ok_or(format!("Day {} is not solved", n))?;
+                    verify(day, input)?
+                }
+                None => days::DAYS
+                    .iter()
+                    .map(|d| verify(d, None))
+                    .sum::<Result<usize, String>>()?,
+            };
+            if failed > 0 {
+                return Err(format!("{} check(s) did not pass", failed));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = parse_args(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    });
+    if let Err(e) = execute(command) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+fn args(s: &str) -> Vec<String> {
+    s.split_whitespace().map(String::from).collect()
+}
+
+#[test]
+fn parse_run_all() {
+    assert_eq!(
+        parse_args(&args("run")),
+        Ok(Command::Run {
+            day: None,
+            part: None,
+            input: None
+        })
+    );
+}
+
+#[test]
+fn parse_run_day() {
+    assert_eq!(
+        parse_args(&args("run 3 --part 2 --input path")),
+        Ok(Command::Run {
+            day: Some(3),
+            part: Some(2),
+            input: Some(PathBuf::from("path"))
+        })
+    );
+}
+
+#[test]
+fn parse_invalid() {
+    assert!(parse_args(&args("")).is_err());
+    assert!(parse_args(&args("run 3 --part 3")).is_err());
+    assert!(parse_args(&args("run x")).is_err());
+    assert!(parse_args(&args("run --input path")).is_err());
+    assert!(parse_args(&args("verify 1 --part 1")).is_err());
+}
+
+#[test]
+fn parse_verify() {
+    assert_eq!(
+        parse_args(&args("verify 2")),
+        Ok(Command::Verify {
+            day: Some(2),
+            input: None
+        })
+    );
+}
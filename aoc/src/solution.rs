@@ -0,0 +1,57 @@
+use std::time::{Duration, Instant};
+
+/// A day's puzzle: the input is parsed once and then handed to both parts.
+/// Malformed input is reported as an error rather than a panic.
+pub trait Solution {
+    type Input;
+
+    fn parse(&self, text: &str) -> Result<Self::Input, String>;
+    fn part1(&self, input: &Self::Input) -> Result<String, String>;
+    fn part2(&self, input: &Self::Input) -> Result<String, String>;
+}
+
+/// Type-erased view of a `Solution` so that all days can live in one table.
+pub trait Runner: Sync {
+    fn run(&self, text: &str, parts: &[u8]) -> Result<Run, String>;
+}
+
+#[derive(Debug)]
+pub struct Answer {
+    pub part: u8,
+    pub value: String,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug)]
+pub struct Run {
+    pub parse: Duration,
+    pub answers: Vec<Answer>,
+}
+
+impl<S: Solution + Sync> Runner for S {
+    fn run(&self, text: &str, parts: &[u8]) -> Result<Run, String> {
+        let start = Instant::now();
+        let input = self
+            .parse(text)
+            .map_err(|e| format!("Invalid input: {}", e))?;
+        let parse = start.elapsed();
+        let answers = parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let value = match part {
+                    1 => self.part1(&input),
+                    2 => self.part2(&input),
+                    _ => panic!("Invalid part: {}", part),
+                }
+                .map_err(|e| format!("Part {} failed: {}", part, e))?;
+                Ok(Answer {
+                    part,
+                    value,
+                    elapsed: start.elapsed(),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Run { parse, answers })
+    }
+}
@@ -0,0 +1,158 @@
+use crate::days::Day;
+use crate::solution::Run;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Known-correct answers, stored as `answers.toml` next to a puzzle input.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct Answers {
+    part1: Option<toml::Value>,
+    part2: Option<toml::Value>,
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Answers, String> {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+
+    /// Reads the answers at `path`, or none if nothing has been recorded
+    /// there yet.
+    pub fn load(path: &Path) -> Result<Answers, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Answers::parse(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn get(&self, part: u8) -> Option<String> {
+        let value = match part {
+            1 => self.part1.as_ref()?,
+            2 => self.part2.as_ref()?,
+            _ => return None,
+        };
+        match value {
+            toml::Value::String(s) => Some(s.clone()),
+            v => Some(v.to_string()),
+        }
+    }
+}
+
+pub fn answers_path(input: &Path) -> PathBuf {
+    input.with_file_name("answers.toml")
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    /// The recorded answer no longer matches
+    Regression {
+        expected: String,
+    },
+    /// Nothing recorded for this part yet
+    Unknown,
+    /// The solution rejected its input or panicked
+    Fail {
+        error: String,
+    },
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Regression { expected } => write!(f, "REGRESSION (expected {})", expected),
+            Status::Unknown => write!(f, "UNKNOWN"),
+            Status::Fail { error } => write!(f, "FAIL ({})", error),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Check {
+    pub part: u8,
+    pub value: Option<String>,
+    pub elapsed: Duration,
+    pub status: Status,
+}
+
+pub fn status(expected: Option<String>, value: &str) -> Status {
+    match expected {
+        Some(e) if e == value => Status::Pass,
+        Some(e) => Status::Regression { expected: e },
+        None => Status::Unknown,
+    }
+}
+
+/// Runs both parts of a day and compares them against the recorded answers.
+pub fn verify(day: &Day, text: &str, answers: &Answers) -> Vec<Check> {
+    let parts = [1, 2];
+    let run = panic::catch_unwind(AssertUnwindSafe(|| day.solution.run(text, &parts)));
+    match run {
+        Ok(Ok(Run { answers: run, .. })) => run
+            .into_iter()
+            .map(|a| Check {
+                part: a.part,
+                status: status(answers.get(a.part), &a.value),
+                value: Some(a.value),
+                elapsed: a.elapsed,
+            })
+            .collect(),
+        Ok(Err(error)) => failed(&parts, &error),
+        Err(_) => failed(&parts, "panicked"),
+    }
+}
+
+fn failed(parts: &[u8], error: &str) -> Vec<Check> {
+    parts
+        .iter()
+        .map(|&part| Check {
+            part,
+            value: None,
+            elapsed: Duration::default(),
+            status: Status::Fail {
+                error: error.to_string(),
+            },
+        })
+        .collect()
+}
+
+#[test]
+fn answers_parse() {
+    let answers = Answers::parse("part1 = 1246\npart2 = \"abc\"\n").unwrap();
+    assert_eq!(answers.get(1), Some("1246".to_string()));
+    assert_eq!(answers.get(2), Some("abc".to_string()));
+    assert_eq!(Answers::default().get(1), None);
+}
+
+#[test]
+fn answers_load() {
+    let dir = std::env::temp_dir().join(format!("aoc-answers-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    assert_eq!(
+        Answers::load(&dir.join("missing.toml")),
+        Ok(Answers::default())
+    );
+    // A directory exists but cannot be read as answers
+    assert!(Answers::load(&dir).is_err());
+    fs::write(dir.join("answers.toml"), b"part1 = \xff").unwrap();
+    assert!(Answers::load(&dir.join("answers.toml")).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn status_works() {
+    assert_eq!(status(Some("1".to_string()), "1"), Status::Pass);
+    assert_eq!(
+        status(Some("1".to_string()), "2"),
+        Status::Regression {
+            expected: "1".to_string()
+        }
+    );
+    assert_eq!(status(None, "1"), Status::Unknown);
+}
@@ -0,0 +1,9 @@
+#!/bin/sh
+# Runs all benchmarks and saves them as a criterion baseline named after the
+# current commit. Compare two commits with:
+#   cargo bench -p aoc --bench days -- --load-baseline <new> --baseline <old>
+set -e
+cd "$(dirname "$0")"
+baseline="$(git rev-parse --short HEAD)"
+cargo bench -p aoc --bench days -- --save-baseline "$baseline" "$@" \
+	| tee bench_output.txt
@@ -0,0 +1,17 @@
+use aoc_common::intcode::Program;
+
+/// Searches for the noun and verb that make `golden` produce `target` in
+/// position 0, returning them encoded as `100 * noun + verb`.
+pub fn find_noun_verb(golden: &Program, target: usize) -> Option<usize> {
+    for noun in 0..100 {
+        for verb in 0..100 {
+            let mut program = golden.clone();
+            program.data[1] = noun;
+            program.data[2] = verb;
+            if program.execute()[0] == target {
+                return Some(noun * 100 + verb);
+            }
+        }
+    }
+    None
+}
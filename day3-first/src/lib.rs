@@ -0,0 +1,39 @@
+use aoc_common::notation::ParseError;
+use aoc_common::segment::{self, Metric};
+
+pub fn solve(s1: &str, s2: &str) -> Result<Option<u64>, ParseError> {
+    segment::solve_with(s1, s2, &Metric::Manhattan).map(|found| found.map(|(_, d)| d))
+}
+
+#[test]
+fn reference_1_test() {
+    assert_eq!(solve("U7,R6,D4,L4", "R8,U5,L5,D3"), Ok(Some(6)));
+}
+
+#[test]
+fn reference_2_test() {
+    assert_eq!(
+        solve(
+            "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+            "U62,R66,U55,R34,D71,R55,D58,R83"
+        ),
+        Ok(Some(159))
+    );
+}
+
+#[test]
+fn reference_3_test() {
+    assert_eq!(
+        solve(
+            "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+            "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7"
+        ),
+        Ok(Some(135))
+    );
+}
+
+#[test]
+fn invalid_wire() {
+    let e = solve("R8,X5", "U1").unwrap_err();
+    assert_eq!((e.token, e.offset), (1, 3));
+}
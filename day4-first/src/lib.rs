@@ -0,0 +1,19 @@
+use aoc_common::password::{Monotonic, Rule, Runs};
+
+/// Digits never decrease and `runs` allows one of their runs
+pub fn rule() -> impl Rule {
+    Monotonic::NonDecreasing.and(runs())
+}
+
+/// At least two adjacent digits are the same
+pub fn runs() -> Runs {
+    Runs::at_least(2)
+}
+
+#[test]
+fn test_reference() {
+    let valid = |n: u64| rule().check(n, &n.to_string());
+    assert!(valid(111111));
+    assert!(!valid(223450));
+    assert!(!valid(123789));
+}
@@ -0,0 +1,19 @@
+use aoc_common::password::{Monotonic, Rule, Runs};
+
+/// Digits never decrease and `runs` allows one of their runs
+pub fn rule() -> impl Rule {
+    Monotonic::NonDecreasing.and(runs())
+}
+
+/// Some digit repeats exactly twice in a row
+pub fn runs() -> Runs {
+    Runs::exactly(2)
+}
+
+#[test]
+fn test_reference() {
+    let valid = |n: u64| rule().check(n, &n.to_string());
+    assert!(valid(112233));
+    assert!(!valid(123444));
+    assert!(valid(111122));
+}